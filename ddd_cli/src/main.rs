@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::{cell::RefCell, rc::Rc};
 use ambassador::{delegatable_trait, Delegate};
 
+#[allow(dead_code)]
 #[delegatable_trait]
 trait DslItem {
     fn name_set(&mut self, item: &'static str);
@@ -19,6 +20,7 @@ trait DslItem {
     fn as_any(&self) -> &dyn Any;
 }
 
+#[allow(dead_code)]
 struct Item {
     name: Option<&'static str>,
     namespace: Option<&'static str>,
@@ -112,14 +114,16 @@ impl RcRefCellItem
         }
     }
 }
+#[allow(dead_code)]
 trait RcRefCellItem {
     fn ancestor (
-        self: &'_ Self,
+        &self,
         distance: usize,
     ) -> Self
     ;
 }
 
+#[allow(dead_code)]
 #[derive(Delegate)]
 #[delegate(DslItem, target = "item")]
 struct DynamicItem {
@@ -127,6 +131,7 @@ struct DynamicItem {
     values: HashMap<String,Rc<RefCell<dyn DslItem>>>,
 }
 
+#[allow(dead_code)]
 #[derive(Delegate)]
 #[delegate(DslItem, target = "item")]
 struct Type {
@@ -45,6 +45,7 @@ impl Node {
         child
     }
 
+    #[allow(dead_code)]
     fn traverse_up<F, P>(&self, on_node: F, stop_predicate: P)
     where
         F: Fn(&Node),
@@ -63,6 +64,7 @@ impl Node {
         }
     }
 
+    #[allow(dead_code)]
     fn traverse_down<F, P>(&self, on_node: &F, stop_predicate: &P)
     where
         F: Fn(&Node),
@@ -80,6 +82,7 @@ impl Node {
         }
     }
 
+    #[allow(dead_code)]
     fn find_parent(&self, condition: impl Fn(&dyn Item) -> bool) -> Option<Weak<RefCell<Node>>> {
         let mut current = self.parent.clone();
         while let Some(node) = current.clone() {
@@ -92,13 +95,14 @@ impl Node {
         None
     }
 
+    #[allow(dead_code)]
     fn find_child<F>(&self, predicate: &F) -> Option<Rc<RefCell<Node>>>
     where
         F: Fn(&Node) -> bool,
     {
         for child_rc in &self.children {
             let child = child_rc.borrow();
-            if predicate(&*child) {
+            if predicate(&child) {
                 return Some(Rc::clone(child_rc));
             }
 
@@ -110,6 +114,7 @@ impl Node {
         None
     }
 
+    #[allow(dead_code)]
     fn filter_and_collect(&self, predicate: impl Fn(&dyn Item) -> bool) -> Vec<Rc<RefCell<Node>>> {
         self.children.iter()
             .filter(|node| predicate(node.borrow().item.as_ref()))
@@ -117,6 +122,7 @@ impl Node {
             .collect()
     }
 
+    #[allow(dead_code)]
     fn serialize_to_yaml(&self) -> Result<String, serde_yaml::Error> {
         serde_yaml::to_string(&self)
     }
@@ -148,7 +154,7 @@ impl Item for SimpleItem {
 
 fn create_tree(depth: usize, breadth: usize) -> Rc<RefCell<Node>> {
     let node = Rc::new(RefCell::new(Node {
-        item: Box::new(SimpleItem { name: format!("Node 0"), namespace: format!("Namespace 0") }),
+        item: Box::new(SimpleItem { name: "Node 0".to_string(), namespace: "Namespace 0".to_string() }),
         me: None,
         parent: None,
         children: Vec::new(),
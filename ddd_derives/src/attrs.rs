@@ -0,0 +1,225 @@
+use syn::spanned::Spanned;
+
+/// Options given to a field of an `AsDslItem` struct through `#[dsl(...)]`.
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// `#[dsl(default = "...")]`: fallback returned by the getter while the field is unset.
+    /// String fields take the literal as is, other types parse it as an expression.
+    pub default: Option<syn::LitStr>,
+    /// `#[dsl(skip)]`: the field is not part of the generated DSL item.
+    pub skip: bool,
+    /// `#[dsl(rename = "...")]`: name used for the getter, the setters and serde.
+    pub rename: Option<syn::LitStr>,
+    /// `#[dsl(required)]`: `dsl{Ident}` fails if the field was not set.
+    pub required: bool,
+    /// `#[dsl(doc = "...")]`: documentation put on the generated getter and setters.
+    pub doc: Option<syn::LitStr>,
+    /// `#[dsl(validate(...))]` and `#[dsl(validate = path)]`: checks run by `validate()`.
+    pub validate: Validate,
+}
+
+/// The checks of a field, several `validate` attributes add up.
+#[derive(Default)]
+pub struct Validate {
+    /// `min_len = n`: minimum number of characters of a string, of items of a `Vec`.
+    pub min_len: Option<syn::LitInt>,
+    /// `regex = "..."`: pattern a string has to match.
+    pub regex: Option<syn::LitStr>,
+    /// `range = "0..=255"`: range a number has to be in.
+    pub range: Option<(syn::LitStr, syn::ExprRange)>,
+    /// `validate = path`: functions taking the getter value and returning `Result<(), String>`.
+    pub custom: Vec<syn::Path>,
+}
+
+impl Validate {
+    pub fn is_empty(&self) -> bool {
+        self.min_len.is_none() && self.regex.is_none() && self.range.is_none() && self.custom.is_empty()
+    }
+
+    fn parse(&mut self, meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.input.peek(syn::Token![=]) {
+            self.custom.push(meta.value()?.parse()?);
+            return Ok(());
+        }
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("min_len") {
+                let min_len: syn::LitInt = meta.value()?.parse()?;
+                min_len.base10_parse::<usize>()?;
+                self.min_len = Some(min_len);
+            } else if meta.path.is_ident("regex") {
+                let regex: syn::LitStr = meta.value()?.parse()?;
+                if let Err(err) = regex::Regex::new(&regex.value()) {
+                    return Err(syn::Error::new(regex.span(), err));
+                }
+                self.regex = Some(regex);
+            } else if meta.path.is_ident("range") {
+                let range: syn::LitStr = meta.value()?.parse()?;
+                let expr = range.parse()?;
+                self.range = Some((range, expr));
+            } else {
+                return Err(meta.error("unsupported validate option"));
+            }
+            Ok(())
+        })
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = FieldAttrs::default();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("dsl")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    attrs.default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("rename") {
+                    let rename: syn::LitStr = meta.value()?.parse()?;
+                    rename.parse::<syn::Ident>()?;
+                    attrs.rename = Some(rename);
+                } else if meta.path.is_ident("required") {
+                    attrs.required = true;
+                } else if meta.path.is_ident("doc") {
+                    attrs.doc = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("validate") {
+                    attrs.validate.parse(&meta)?;
+                } else {
+                    return Err(meta.error("unsupported dsl attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if attrs.skip
+            && (attrs.default.is_some()
+                || attrs.rename.is_some()
+                || attrs.required
+                || !attrs.validate.is_empty())
+        {
+            return Err(syn::Error::new(
+                field.span(),
+                "a skipped field can't have other dsl attributes",
+            ));
+        }
+        Ok(attrs)
+    }
+}
+
+/// Options given to an `AsDslItem` struct through `#[dsl(...)]`.
+#[derive(Default)]
+pub struct ItemAttrs {
+    /// `#[dsl(extends = Base)]`: the item embeds `DslBaseImpl` and implements its traits.
+    pub extends: Option<syn::Path>,
+    /// `#[dsl(type_map(ty = T, ...))]`: field types the item supports on top of the built-in ones.
+    pub type_maps: Vec<TypeMapAttrs>,
+}
+
+/// A user type mapping, `#[dsl(type_map(ty = T, getter = G, borrow = .., default = .., setter = S, into = .., source = ..))]`.
+///
+/// `borrow` turns `value: &T` into `G` and `into` turns `value: S` into `T`,
+/// both are needed as soon as the getter or setter type differs from `T`.
+/// `source` turns `value: &T` into the Rust source of a setter argument for
+/// `to_dsl_source`, values are printed with `Debug` without it.
+pub struct TypeMapAttrs {
+    pub ty: syn::Type,
+    pub getter: Option<syn::Type>,
+    pub borrow: Option<syn::Expr>,
+    pub default: Option<syn::Expr>,
+    pub setter: Option<syn::Type>,
+    pub into: Option<syn::Expr>,
+    pub source: Option<syn::Expr>,
+}
+
+impl TypeMapAttrs {
+    fn parse(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Self> {
+        let mut ty = None;
+        let mut getter = None;
+        let mut borrow = None;
+        let mut default = None;
+        let mut setter = None;
+        let mut into = None;
+        let mut source = None;
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ty") {
+                ty = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("getter") {
+                getter = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("borrow") {
+                borrow = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("setter") {
+                setter = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("into") {
+                into = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("source") {
+                source = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unsupported type_map option"));
+            }
+            Ok(())
+        })?;
+
+        let Some(ty) = ty else {
+            return Err(meta.error("type_map needs the mapped type, `ty = ...`"));
+        };
+        if getter.is_some() && borrow.is_none() {
+            return Err(meta.error("a type_map with `getter` also needs `borrow`"));
+        }
+        if setter.is_some() && into.is_none() {
+            return Err(meta.error("a type_map with `setter` also needs `into`"));
+        }
+        Ok(TypeMapAttrs { ty, getter, borrow, default, setter, into, source })
+    }
+}
+
+impl ItemAttrs {
+    pub fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut item_attrs = ItemAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("dsl")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("extends") {
+                    item_attrs.extends = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("type_map") {
+                    item_attrs.type_maps.push(TypeMapAttrs::parse(&meta)?);
+                } else {
+                    return Err(meta.error("unsupported dsl attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(item_attrs)
+    }
+}
+
+/// Options given to a variant of an `AsDslEnum` enum through `#[dsl(...)]`.
+#[derive(Default)]
+pub struct LiteralAttrs {
+    /// `#[dsl(rename = "...")]`: literal name used by `Display`, `parse` and serde.
+    pub rename: Option<syn::LitStr>,
+    /// `#[dsl(value = "...")]`: value associated with the literal.
+    pub value: Option<syn::LitStr>,
+    /// `#[dsl(doc = "...")]`: documentation put on the generated literal.
+    pub doc: Option<syn::LitStr>,
+}
+
+impl LiteralAttrs {
+    pub fn parse(variant: &syn::Variant) -> syn::Result<Self> {
+        let mut attrs = LiteralAttrs::default();
+        for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("dsl")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    attrs.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("value") {
+                    attrs.value = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("doc") {
+                    attrs.doc = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unsupported dsl attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+}
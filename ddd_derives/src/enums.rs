@@ -0,0 +1,174 @@
+use crate::attrs::LiteralAttrs;
+use quote::{format_ident, quote};
+
+pub fn derive(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &ast.ident;
+    let dsl_ident = format_ident!("Dsl{ident}Impl");
+    let trait_ident_get = format_ident!("Dsl{ident}Get");
+    let dsl_ident_builder = format_ident!("dsl{ident}");
+    let dsl_ident_builder_default = format_ident!("dsl{ident}Default");
+
+    let variants = match &ast.data {
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => variants,
+        syn::Data::Struct(syn::DataStruct { struct_token, .. }) => {
+            return Err(unsupported_shape(struct_token))
+        }
+        syn::Data::Union(syn::DataUnion { union_token, .. }) => {
+            return Err(unsupported_shape(union_token))
+        }
+    };
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "derive(AsDslEnum) needs at least one variant",
+        ));
+    }
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.generics,
+            "derive(AsDslEnum) doesn't support generic enums",
+        ));
+    }
+
+    let mut literals = Vec::new();
+    for variant in variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                "derive(AsDslEnum) only supports variants without fields, \
+                 use #[dsl(value = \"...\")] to associate a value",
+            ));
+        }
+        if let Some((_, discriminant)) = &variant.discriminant {
+            return Err(syn::Error::new_spanned(
+                discriminant,
+                "derive(AsDslEnum) assigns ordinals in declaration order, remove the discriminant",
+            ));
+        }
+        let attrs = LiteralAttrs::parse(variant)?;
+        let name = attrs
+            .rename
+            .as_ref()
+            .map(syn::LitStr::value)
+            .unwrap_or_else(|| variant.ident.to_string());
+        literals.push((&variant.ident, name, attrs));
+    }
+
+    let variant_defs = literals.iter().map(|(variant, name, attrs)| {
+        let doc = attrs.doc.as_ref().map(|doc| quote! { #[doc = #doc] });
+        quote! {
+            #doc
+            #[serde(rename = #name)]
+            #variant
+        }
+    });
+    let variant_idents = literals.iter().map(|(variant, _, _)| variant).collect::<Vec<_>>();
+    let first = variant_idents[0];
+    let names = literals.iter().map(|(_, name, _)| name).collect::<Vec<_>>();
+    let ordinals = 0..literals.len();
+    let values = literals.iter().map(|(_, _, attrs)| match &attrs.value {
+        Some(value) => quote! { ::ddd_runtime::__private::Option::Some(#value) },
+        None => quote! { ::ddd_runtime::__private::Option::None },
+    });
+    let variant_sources = variant_idents.iter().map(|variant| format!("{dsl_ident}::{variant}"));
+    let unknown_literal = format!("unknown {ident} literal `{{}}`");
+
+    let typetag_scope = crate::typetag_scope();
+
+    Ok(quote! {
+        #typetag_scope
+
+        #[::ddd_runtime::__private::typetag::serde(tag = "type")]
+        pub trait #trait_ident_get {
+            fn name_get(&self) -> &'static str;
+            fn ordinal_get(&self) -> usize;
+            fn value_get(&self) -> ::ddd_runtime::__private::Option<&'static str>;
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ::ddd_runtime::__private::serde::Serialize, ::ddd_runtime::__private::serde::Deserialize)]
+        #[serde(crate = "::ddd_runtime::__private::serde")]
+        pub enum #dsl_ident {
+            #(#variant_defs),*
+        }
+
+        impl #dsl_ident {
+            /// All literals in declaration order, the index is the ordinal.
+            pub const LITERALS: &'static [#dsl_ident] = &[#(#dsl_ident::#variant_idents),*];
+
+            /// The literal with the given name, as printed by `Display`.
+            pub fn parse(name: &str) -> ::ddd_runtime::__private::Option<Self> {
+                match name {
+                    #(#names => ::ddd_runtime::__private::Option::Some(#dsl_ident::#variant_idents),)*
+                    _ => ::ddd_runtime::__private::Option::None,
+                }
+            }
+
+            pub fn from_ordinal(ordinal: usize) -> ::ddd_runtime::__private::Option<Self> {
+                Self::LITERALS.get(ordinal).copied()
+            }
+        }
+
+        #[::ddd_runtime::__private::typetag::serde]
+        impl #trait_ident_get for #dsl_ident {
+            fn name_get(&self) -> &'static str {
+                match self {
+                    #(#dsl_ident::#variant_idents => #names,)*
+                }
+            }
+
+            fn ordinal_get(&self) -> usize {
+                match self {
+                    #(#dsl_ident::#variant_idents => #ordinals,)*
+                }
+            }
+
+            fn value_get(&self) -> ::ddd_runtime::__private::Option<&'static str> {
+                match self {
+                    #(#dsl_ident::#variant_idents => #values,)*
+                }
+            }
+        }
+
+        impl ::ddd_runtime::__private::Default for #dsl_ident {
+            fn default() -> Self {
+                #dsl_ident::#first
+            }
+        }
+
+        impl ::ddd_runtime::__private::fmt::Display for #dsl_ident {
+            fn fmt(&self, f: &mut ::ddd_runtime::__private::fmt::Formatter<'_>) -> ::ddd_runtime::__private::fmt::Result {
+                f.write_str(#trait_ident_get::name_get(self))
+            }
+        }
+
+        impl ::ddd_runtime::source::ToDslSource for #dsl_ident {
+            fn to_dsl_source(&self) -> ::ddd_runtime::__private::String {
+                match self {
+                    #(#dsl_ident::#variant_idents => #variant_sources.to_owned(),)*
+                }
+            }
+        }
+
+        impl ::ddd_runtime::__private::FromStr for #dsl_ident {
+            type Err = ::ddd_runtime::__private::String;
+
+            fn from_str(name: &str) -> ::ddd_runtime::__private::Result<Self, Self::Err> {
+                Self::parse(name).ok_or_else(|| ::ddd_runtime::__private::format!(#unknown_literal, name))
+            }
+        }
+
+        pub fn #dsl_ident_builder_default() -> #dsl_ident {
+            #dsl_ident::#first
+        }
+
+        pub fn #dsl_ident_builder(adapt: impl FnOnce(&mut #dsl_ident)) -> #dsl_ident {
+            let mut ret = #dsl_ident_builder_default();
+            adapt(&mut ret);
+            ret
+        }
+    })
+}
+
+fn unsupported_shape<T: quote::ToTokens>(tokens: T) -> syn::Error {
+    syn::Error::new_spanned(tokens, "derive(AsDslEnum) only supports enums")
+}
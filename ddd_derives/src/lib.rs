@@ -2,10 +2,17 @@ extern crate proc_macro2;
 extern crate quote;
 extern crate syn;
 
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, DeriveInput};
+mod attrs;
+mod enums;
+mod types;
+mod visitors;
 
-#[proc_macro_derive(AsDslItem)]
+use attrs::{FieldAttrs, ItemAttrs};
+use types::{TypeMapping, TypeRegistry};
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_macro_input, parse_quote, DeriveInput};
+
+#[proc_macro_derive(AsDslItem, attributes(dsl))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let ident = &ast.ident;
@@ -15,206 +22,1425 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let trait_ident_set = format_ident!("Dsl{ident}Set");
     let dsl_ident_builder = format_ident!("dsl{ident}");
     let dsl_ident_builder_default = format_ident!("dsl{ident}Default");
+    let dsl_ident_try_builder = format_ident!("try_dsl{ident}");
+    let try_builder_doc = format!(
+        " Like `dsl{ident}`, returning the names of the required fields left unset instead of panicking."
+    );
 
-    let fields = match &ast {
-        syn::DeriveInput {
-            data:
-                syn::Data::Struct(syn::DataStruct {
-                    fields: syn::Fields::Named(syn::FieldsNamed { named: fields, .. }),
-                    ..
-                }),
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(syn::FieldsNamed { named: fields, .. }),
+            ..
+        }) => fields,
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Unnamed(fields),
             ..
-        } => fields,
-        _ => unimplemented!("derive(AsDslItem) only supports structs with named fields"),
+        }) => return unsupported_shape(fields),
+        syn::Data::Struct(syn::DataStruct { .. }) => return unsupported_shape(ident),
+        syn::Data::Enum(syn::DataEnum { enum_token, .. }) => return unsupported_shape(enum_token),
+        syn::Data::Union(syn::DataUnion { union_token, .. }) => {
+            return unsupported_shape(union_token)
+        }
+    };
+
+    let item_attrs = match ItemAttrs::parse(&ast.attrs) {
+        Ok(item_attrs) => item_attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let registry = TypeRegistry::new(&item_attrs.type_maps);
+    let type_params = ast.generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+    let fields = match fields
+        .iter()
+        .map(|field| DslField::new(field, &type_params, &registry))
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(fields) => fields.into_iter().filter(|field| !field.attrs.skip).collect::<Vec<_>>(),
+        Err(err) => return err.to_compile_error().into(),
     };
+    if let Some(field) = fields.iter().find(|field| field.id == "derived_from") {
+        return syn::Error::new_spanned(
+            &field.id,
+            "`derived_from` is reserved for the template recorded by `merge_from`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Getters and setters are implemented once, on `Dsl{Ident}Impl`. Every other
+    // implementor of the traits only hands out its embedded `Dsl{Ident}Impl`
+    // and inherits them as default methods, which is what `extends` builds on.
+    let base_fn = format_ident!("dsl_{}", to_snake_case(&ident.to_string()));
+    let base_fn_mut = format_ident!("{base_fn}_mut");
+    let base = item_attrs.extends.as_ref().map(Base::new);
+
+    // typetag can only register concrete types, so generic items get plain traits
+    // and can't extend another item, which would need a registered Get impl.
+    // Without extending items there is nothing to forward to either, and their
+    // traits only declare the accessors.
+    let generics = &ast.generics;
+    let is_generic = !generics.params.is_empty();
+    if let (true, Some(extends)) = (is_generic, &item_attrs.extends) {
+        return syn::Error::new_spanned(
+            extends,
+            "#[dsl(extends)] is not supported on generic items, \
+             typetag can't register generic implementations",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // Missing fields are taken from `Default`, which needs the type parameters to be `Default`.
+    let serde_bound = is_generic.then(|| {
+        let bounds = type_params
+            .iter()
+            .map(|param| format!("{param}: ::ddd_runtime::__private::serde::Deserialize<'de> + ::ddd_runtime::__private::Default"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        quote! { #[serde(bound(deserialize = #bounds))] }
+    });
+    let typetag_scope = (!is_generic).then(typetag_scope);
+    let typetag_trait = (!is_generic).then(|| quote! { #[::ddd_runtime::__private::typetag::serde(tag = "type")] });
+    let typetag_impl = (!is_generic).then(|| quote! { #[::ddd_runtime::__private::typetag::serde] });
+
+    // The empty values of type parameters come from their `Default` implementation.
+    let mut default_generics = generics.clone();
+    let default_where = default_generics.make_where_clause();
+    for param in &type_params {
+        default_where.predicates.push(parse_quote! { #param: ::ddd_runtime::__private::Default });
+    }
+    let default_where = &*default_where;
+
+    // Merging clones the template's values.
+    let mut clone_generics = generics.clone();
+    let clone_where = clone_generics.make_where_clause();
+    for param in &type_params {
+        clone_where.predicates.push(parse_quote! { #param: ::ddd_runtime::__private::Clone });
+    }
+    let clone_where = &*clone_where;
+
+    let mut blanket_generics = generics.clone();
+    blanket_generics.params.push(parse_quote! {
+        DslSelf: ?Sized + #trait_ident_set #ty_generics + #trait_ident_get #ty_generics
+    });
+    let (blanket_impl_generics, _, _) = blanket_generics.split_for_impl();
+
+    let base_fn_def = (!is_generic).then(|| {
+        quote! {
+            #[doc(hidden)]
+            fn #base_fn(&self) -> &#dsl_ident;
+        }
+    });
+    let base_fn_mut_def = (!is_generic).then(|| {
+        quote! {
+            #[doc(hidden)]
+            fn #base_fn_mut(&mut self) -> &mut #dsl_ident;
+        }
+    });
+    let base_fn_impl = (!is_generic).then(|| {
+        quote! {
+            fn #base_fn(&self) -> &#dsl_ident {
+                self
+            }
+        }
+    });
+    let base_fn_mut_impl = (!is_generic).then(|| {
+        quote! {
+            fn #base_fn_mut(&mut self) -> &mut #dsl_ident {
+                self
+            }
+        }
+    });
+
+    // Keeps lifetimes and type parameters that only skipped fields use.
+    let marker = is_generic.then(|| {
+        let lifetimes = generics.lifetimes().map(|param| &param.lifetime);
+        quote! { ::ddd_runtime::__private::PhantomData<fn() -> (#(&#lifetimes (),)* #(#type_params,)*)> }
+    });
+    let marker_field = marker.as_ref().map(|marker| {
+        quote! {
+            #[serde(skip)]
+            dsl_marker: #marker,
+        }
+    });
+    let marker_default = is_generic.then(|| {
+        quote! {
+            dsl_marker: ::ddd_runtime::__private::PhantomData,
+        }
+    });
 
     let dsl_fields = fields.iter().map(|field| {
-        let field = field.clone();
-        let id = field.ident.unwrap();
+        let id = &field.id;
         let id_empty = format_ident!("{id}_empty");
-        let ty = std::option::Option::Some(&field.ty);
+        let ty = field.ty;
+        let serde_rename = field.attrs.rename.as_ref().map(|rename| {
+            quote! { #[serde(rename = #rename)] }
+        });
 
-        if is_box_type(&field.ty) {
-            quote! {
-                #id: std::option::Option<#ty>
+        // Only explicitly set values are written, the empty values come from `Default`.
+        match field_kind(field.ty) {
+            FieldKind::Vec(item_ty) => {
+                let item_ty = field.storage(item_ty);
+                quote! {
+                    #serde_rename
+                    #[serde(skip_serializing_if = "::ddd_runtime::__private::Vec::is_empty")]
+                    #id: ::ddd_runtime::__private::Vec<#item_ty>
+                }
             }
-        } else {
-            quote! {
-                #id: std::option::Option<#ty>,
-                #id_empty: #ty
+            FieldKind::Option(value_ty) => {
+                let value_ty = field.storage(value_ty);
+                quote! {
+                    #serde_rename
+                    #[serde(skip_serializing_if = "::ddd_runtime::__private::Option::is_none")]
+                    #id: ::ddd_runtime::__private::Option<#value_ty>
+                }
+            }
+            FieldKind::Box => quote! {
+                #serde_rename
+                #[serde(skip_serializing_if = "::ddd_runtime::__private::Option::is_none")]
+                #id: ::ddd_runtime::__private::Option<#ty>
+            },
+            FieldKind::Scalar => {
+                let ty = field.storage(ty);
+                quote! {
+                    #serde_rename
+                    #[serde(skip_serializing_if = "::ddd_runtime::__private::Option::is_none")]
+                    #id: ::ddd_runtime::__private::Option<#ty>,
+                    #[serde(skip)]
+                    #id_empty: #ty
+                }
             }
         }
     });
 
     let dsl_defaults = fields.iter().map(|field| {
-        let field = field.clone();
-        let id = field.ident.unwrap();
+        let id = &field.id;
         let id_empty = format_ident!("{id}_empty");
-        let ty = &field.ty;
-        let empty_value = empty_value(&ty);
+        let empty_value = field.empty_value();
 
-        if is_box_type(&field.ty) {
-            quote! {
-                #id: std::option::Option::None
-            }
-        } else {
-            quote! {
-                #id: std::option::Option::None,
+        match field_kind(field.ty) {
+            FieldKind::Vec(_) => quote! {
+                #id: ::ddd_runtime::__private::Vec::new()
+            },
+            FieldKind::Option(_) | FieldKind::Box => quote! {
+                #id: ::ddd_runtime::__private::Option::None
+            },
+            FieldKind::Scalar => quote! {
+                #id: ::ddd_runtime::__private::Option::None,
                 #id_empty: #empty_value
-            }
+            },
         }
     });
 
     let getters_def = fields.iter().map(|field| {
-        let field = field.clone();
-        let id = field.ident.unwrap();
-        let id_get = format_ident!("{id}_get");
-        let ty = field.ty;
-        let ty_ref = dsl_type_ref(&ty);
+        let name = &field.name;
+        let id_get = format_ident!("{name}_get");
+        let id_is_set = format_ident!("is_{name}_set");
+        let ty_ref = field.getter_type();
+        let doc = field.doc();
 
+        if is_generic {
+            return quote! {
+                #doc
+                fn #id_get(&self) -> #ty_ref;
+                fn #id_is_set(&self) -> bool;
+            };
+        }
         quote! {
-            fn #id_get(&self) -> #ty_ref;
+            #doc
+            fn #id_get(&self) -> #ty_ref {
+                #trait_ident_get::#id_get(self.#base_fn())
+            }
+
+            fn #id_is_set(&self) -> bool {
+                #trait_ident_get::#id_is_set(self.#base_fn())
+            }
         }
     });
 
     let getters = fields.iter().map(|field| {
-        let field = field.clone();
-        let id = field.ident.unwrap();
-        let id_get = format_ident!("{id}_get");
+        let id = &field.id;
         let id_empty = format_ident!("{id}_empty");
-        let ty = &field.ty;
-        let ty_ref = dsl_type_ref(&ty);
+        let name = &field.name;
+        let id_get = format_ident!("{name}_get");
+        let ty_ref = field.getter_type();
 
-        if is_box_type(&field.ty) {
-            quote! {
-                fn #id_get(&self) -> #ty_ref {
-                    &self.#id
+        let value = match (field_kind(field.ty), &field.mapping) {
+            (FieldKind::Vec(_) | FieldKind::Box, _) => quote! { &self.#id },
+            (FieldKind::Option(_), Some(TypeMapping { borrow: Some(borrow), .. })) => quote! {
+                self.#id.as_ref().map(|value| #borrow)
+            },
+            (FieldKind::Option(_), _) => quote! { self.#id.as_ref() },
+            (FieldKind::Scalar, mapping) => {
+                let value = quote! { self.#id.as_ref().unwrap_or(&self.#id_empty) };
+                match mapping {
+                    Some(mapping) => mapping.borrow(value),
+                    None => value,
                 }
             }
-        } else {
+        };
+        let id_is_set = format_ident!("is_{name}_set");
+        let is_set = field.is_set();
+        quote! {
+            fn #id_get(&self) -> #ty_ref {
+                #value
+            }
+
+            fn #id_is_set(&self) -> bool {
+                #is_set
+            }
+        }
+    });
+
+    let setters_def = fields.iter().map(|field| {
+        let doc = field.doc();
+        let methods = field.setter_methods().into_iter().map(|method| {
+            let name = method.name;
+            let arg_names = method.args.iter().map(|(arg, _)| arg);
+            let args = method.args.iter().map(|(arg, ty)| quote! { #arg: #ty });
+
+            if is_generic {
+                return quote! {
+                    #doc
+                    fn #name(&mut self, #(#args),*) -> &mut Self;
+                };
+            }
             quote! {
-                fn #id_get(&self) -> #ty_ref {
-                    match &self.#id {
-                        Some(v) => &v,
-                        None => &self.#id_empty,
-                    }
+                #doc
+                fn #name(&mut self, #(#args),*) -> &mut Self {
+                    #trait_ident_set::#name(self.#base_fn_mut(), #(#arg_names),*);
+                    self
                 }
             }
+        });
+
+        quote! {
+            #(#methods)*
         }
     });
 
     let setters = fields.iter().map(|field| {
-        let field = field.clone();
-        let id = field.ident.unwrap();
+        let methods = field.setter_methods().into_iter().map(|method| {
+            let name = method.name;
+            let args = method.args.iter().map(|(arg, ty)| quote! { #arg: #ty });
+            let body = method.body;
 
-        if is_string(&field.ty) {
             quote! {
-                fn #id(&mut self, value: &str) -> &mut Self {
-                    self.#id = std::option::Option::Some(value.to_owned());
+                fn #name(&mut self, #(#args),*) -> &mut Self {
+                    #body
                     self
                 }
             }
-        } else {
-            let ty = std::option::Option::Some(&field.ty);
+        });
+
+        quote! {
+            #(#methods)*
+        }
+    });
+
+    let required_checks = fields.iter().filter(|field| field.attrs.required).map(|field| {
+        let name = field.name.to_string();
+        let is_unset = field.is_unset();
+        quote! {
+            if #is_unset {
+                missing.push(#name);
+            }
+        }
+    });
+
+    let merges = fields.iter().map(|field| {
+        let id = &field.id;
+        let is_unset = field.is_unset();
+        quote! {
+            if #is_unset {
+                self.#id = other.#id.clone();
+            }
+        }
+    });
+
+    let set_checks = fields.iter().map(|field| {
+        let name = field.name.to_string();
+        let is_set = field.is_set();
+        quote! {
+            if #is_set {
+                set.push(#name);
+            }
+        }
+    });
+
+    // Items behind `Box<dyn Dsl{Ident}Get>` stay reflectable, printable and clonable,
+    // and can be shared between threads. Generic items implement `Reflect` and
+    // `ToDslSource` only for suitable type parameters.
+    let base_get = match (&base, is_generic) {
+        (Some(base), _) => {
+            let get = &base.get;
+            Some(quote! { : #get })
+        }
+        (None, false) => Some(quote! {
+            : ::ddd_runtime::reflect::Reflect + ::ddd_runtime::source::ToDslSource
+                + ::ddd_runtime::__private::DynClone
+                + ::ddd_runtime::__private::Send + ::ddd_runtime::__private::Sync
+        }),
+        (None, true) => None,
+    };
+    // Lets items hold other items as `Box<dyn Dsl{Ident}Get>` fields.
+    let trait_object_impls = (base.is_none() && !is_generic).then(|| {
+        quote! {
+            ::ddd_runtime::__private::dyn_clone::clone_trait_object!(#trait_ident_get);
+
+            impl ::ddd_runtime::__private::fmt::Debug for dyn #trait_ident_get {
+                fn fmt(&self, f: &mut ::ddd_runtime::__private::fmt::Formatter<'_>) -> ::ddd_runtime::__private::fmt::Result {
+                    ::ddd_runtime::__private::reflect_debug(self, f)
+                }
+            }
+
+            impl ::ddd_runtime::__private::PartialEq for dyn #trait_ident_get {
+                fn eq(&self, other: &Self) -> bool {
+                    ::ddd_runtime::__private::reflect_eq(self, other)
+                }
+            }
+        }
+    });
+    let base_set = base.as_ref().map(|base| {
+        let set = &base.set;
+        quote! { : #set }
+    });
+    let base_trait = base.as_ref().map(|base| {
+        let item = &base.item;
+        quote! { + #item }
+    });
+    let base_field = base.as_ref().map(|base| {
+        let field = &base.field;
+        let dsl_impl = &base.dsl_impl;
+        quote! {
+            #[serde(flatten)]
+            #field: #dsl_impl,
+        }
+    });
+    let field_descriptors = fields.iter().map(|field| {
+        let name = field.name.to_string();
+        let ty = type_string(field.ty);
+        let kind = match field_kind(field.ty) {
+            FieldKind::Scalar => quote! { Scalar },
+            FieldKind::Vec(_) => quote! { List },
+            FieldKind::Box => quote! { Box },
+            FieldKind::Option(_) => quote! { Option },
+        };
+        let default = match &field.attrs.default {
+            Some(default) => quote! { ::ddd_runtime::__private::Option::Some(#default) },
+            None => quote! { ::ddd_runtime::__private::Option::None },
+        };
+        let doc = match &field.attrs.doc {
+            Some(doc) => quote! { ::ddd_runtime::__private::Option::Some(#doc) },
+            None => quote! { ::ddd_runtime::__private::Option::None },
+        };
+        let required = field.attrs.required;
+        quote! {
+            ::ddd_runtime::reflect::FieldDescriptor {
+                name: #name,
+                ty: #ty,
+                kind: ::ddd_runtime::reflect::FieldKind::#kind,
+                default: #default,
+                doc: #doc,
+                required: #required,
+            }
+        }
+    });
+    let descriptor_extends = match &base {
+        Some(base) => {
+            let dsl_impl = &base.dsl_impl;
+            quote! { ::ddd_runtime::__private::Option::Some(&#dsl_impl::DESCRIPTOR) }
+        }
+        None => quote! { ::ddd_runtime::__private::Option::None },
+    };
+
+    let get_fields = fields.iter().map(|field| {
+        let id = &field.id;
+        let id_empty = format_ident!("{id}_empty");
+        let name = field.name.to_string();
+        let value = match field_kind(field.ty) {
+            FieldKind::Scalar => quote! { self.#id.as_ref().unwrap_or(&self.#id_empty) },
+            _ => quote! { &self.#id },
+        };
+        quote! {
+            #name => ::ddd_runtime::__private::reflect_get(name, #value),
+        }
+    });
+    let set_fields = fields.iter().map(|field| {
+        let id = &field.id;
+        let name = field.name.to_string();
+        quote! {
+            #name => ::ddd_runtime::__private::reflect_set(name, &mut self.#id, value),
+        }
+    });
+    let references = fields
+        .iter()
+        .filter(|field| field.mapping.as_ref().is_some_and(|mapping| mapping.reference))
+        .map(|field| {
+            let id = &field.id;
+            let name = field.name.to_string();
+            // Scalars are stored as `Option` as well, unset values are skipped.
             quote! {
-                fn #id(&mut self, value: #ty) -> &mut Self {
-                    self.#id = std::option::Option::Some(value);
-                    self
+                for value in self.#id.iter() {
+                    references.push((#name, value as &dyn ::ddd_runtime::reference::AnyRef));
                 }
             }
+        });
+    let base_references = base.as_ref().map(|base| {
+        let field = &base.field;
+        quote! {
+            references.extend(::ddd_runtime::reflect::Reflect::references(&self.#field));
         }
     });
+    let (get_other_field, set_other_field) = match &base {
+        Some(base) => {
+            let field = &base.field;
+            (
+                quote! { ::ddd_runtime::reflect::Reflect::get_field(&self.#field, name) },
+                quote! { ::ddd_runtime::reflect::Reflect::set_field(&mut self.#field, name, value) },
+            )
+        }
+        None => (
+            quote! { ::ddd_runtime::__private::Result::Err(::ddd_runtime::reflect::ReflectError::UnknownField(name.to_owned())) },
+            quote! { ::ddd_runtime::__private::Result::Err(::ddd_runtime::reflect::ReflectError::UnknownField(name.to_owned())) },
+        ),
+    };
+    let mut reflect_generics = generics.clone();
+    let reflect_where = reflect_generics.make_where_clause();
+    for param in &type_params {
+        reflect_where.predicates.push(parse_quote! {
+            #param: ::ddd_runtime::__private::serde::Serialize + ::ddd_runtime::__private::serde::de::DeserializeOwned
+        });
+    }
+    let reflect_where = &*reflect_where;
 
+    let source_statements = fields.iter().map(|field| {
+        let id = &field.id;
+        let arg = match &field.mapping {
+            Some(mapping) => mapping.source.clone(),
+            None => quote! { ::ddd_runtime::source::ToDslSource::to_dsl_source(value) },
+        };
+        match field_kind(field.ty) {
+            FieldKind::Vec(_) => {
+                let setter = format!("add_{}", field.name);
+                quote! {
+                    for value in self.#id.iter() {
+                        statements.push(::ddd_runtime::__private::format!("o.{}({});", #setter, #arg));
+                    }
+                }
+            }
+            _ => {
+                let setter = field.name.to_string();
+                quote! {
+                    if let ::ddd_runtime::__private::Option::Some(value) = &self.#id {
+                        statements.push(::ddd_runtime::__private::format!("o.{}({});", #setter, #arg));
+                    }
+                }
+            }
+        }
+    });
+    let base_source = base.as_ref().map(|base| {
+        let field = &base.field;
+        quote! {
+            self.#field.dsl_source_statements(statements);
+        }
+    });
+    let mut source_generics = generics.clone();
+    let source_where = source_generics.make_where_clause();
+    for param in &type_params {
+        source_where.predicates.push(parse_quote! {
+            #param: ::ddd_runtime::source::ToDslSource
+        });
+    }
+    let source_where = &*source_where;
 
-    let setters_def = fields.iter().map(|field| {
-        let field = field.clone();
-        let id = field.ident.unwrap();
+    let base_default = base.as_ref().map(|base| {
+        let field = &base.field;
+        let builder_default = &base.builder_default;
+        quote! {
+            #field: #builder_default(),
+        }
+    });
+    let item_name = ident.to_string();
+    let validations = fields.iter().map(|field| {
+        let getter = format_ident!("{}_get", field.name);
+        let value = quote! { <Self as #trait_ident_get #ty_generics>::#getter(self) };
+        field.validation(&item_name, value)
+    });
+    let base_validate = base.as_ref().map(|base| {
+        let field = &base.field;
+        quote! {
+            diagnostics.extend(self.#field.validate());
+        }
+    });
 
-        if is_string(&field.ty) {
-            quote! {
-                fn #id(&mut self, value: &str) -> &mut Self;
+    let base_required = base.as_ref().map(|base| {
+        let field = &base.field;
+        quote! {
+            missing.extend(self.#field.missing_required_fields());
+        }
+    });
+    let base_explicitly_set = base.as_ref().map(|base| {
+        let field = &base.field;
+        quote! {
+            set.extend(self.#field.explicitly_set_fields());
+        }
+    });
+    let base_merge = base.as_ref().map(|base| {
+        let field = &base.field;
+        quote! {
+            self.#field.merge_fields(&other.#field);
+        }
+    });
+    let base_impls = base.as_ref().map(|base| {
+        let Base { field, dsl_impl, get, set, base_fn, base_fn_mut, .. } = base;
+        quote! {
+            impl #set for #dsl_ident {
+                fn #base_fn_mut(&mut self) -> &mut #dsl_impl {
+                    &mut self.#field
+                }
             }
-        } else {
-            let ty = std::option::Option::Some(&field.ty);
-            quote! {
-                fn #id(&mut self, value: #ty) -> &mut Self;
+
+            #[::ddd_runtime::__private::typetag::serde]
+            impl #get for #dsl_ident {
+                fn #base_fn(&self) -> &#dsl_impl {
+                    &self.#field
+                }
             }
         }
     });
 
+    // `derived_from` is left out of `Debug` and `PartialEq`: an item merged from a
+    // template equals the same item read back from YAML.
+    let compared_fields = base
+        .as_ref()
+        .map(|base| &base.field)
+        .into_iter()
+        .chain(fields.iter().map(|field| &field.id))
+        .collect::<Vec<_>>();
+    let compared_names = compared_fields
+        .iter()
+        .map(|field| field.to_string().trim_start_matches("r#").to_owned());
+    let dsl_ident_name = dsl_ident.to_string();
+    let mut debug_generics = generics.clone();
+    let debug_where = debug_generics.make_where_clause();
+    for param in &type_params {
+        debug_where.predicates.push(parse_quote! { #param: ::ddd_runtime::__private::fmt::Debug });
+    }
+    let debug_where = &*debug_where;
+    let mut eq_generics = generics.clone();
+    let eq_where = eq_generics.make_where_clause();
+    for param in &type_params {
+        eq_where.predicates.push(parse_quote! { #param: ::ddd_runtime::__private::PartialEq });
+    }
+    let eq_where = &*eq_where;
+
+    let builder_name = dsl_ident_builder.to_string();
+    let builder_default_name = dsl_ident_builder_default.to_string();
     let output = quote! {
-        #[typetag::serde(tag = "type")]
-        pub trait #trait_ident_get {
+        #typetag_scope
+
+        #typetag_trait
+        pub trait #trait_ident_get #impl_generics #base_get #where_clause {
+            #base_fn_def
             #(#getters_def)*
         }
 
-        pub trait #trait_ident_set {
+        #trait_object_impls
+
+        pub trait #trait_ident_set #impl_generics #base_set #where_clause {
+            #base_fn_mut_def
             #(#setters_def)*
         }
 
-        pub trait #trait_ident : #trait_ident_set + #trait_ident_get {}
+        pub trait #trait_ident #impl_generics :
+            #trait_ident_set #ty_generics + #trait_ident_get #ty_generics #base_trait #where_clause {}
+
+        impl #blanket_impl_generics #trait_ident #ty_generics for DslSelf #where_clause {}
+
+        #[derive(Clone, ::ddd_runtime::__private::serde::Serialize, ::ddd_runtime::__private::serde::Deserialize)]
+        #[serde(crate = "::ddd_runtime::__private::serde", default)]
+        #serde_bound
+        pub struct #dsl_ident #generics #where_clause {
+            #base_field
+            #(#dsl_fields,)*
+            #[serde(skip)]
+            derived_from: ::ddd_runtime::__private::Option<::ddd_runtime::__private::Box<#dsl_ident #ty_generics>>,
+            #marker_field
+        }
+
+        #base_impls
+
+        impl #impl_generics #dsl_ident #ty_generics #where_clause {
+            pub const DESCRIPTOR: ::ddd_runtime::reflect::ItemDescriptor = ::ddd_runtime::reflect::ItemDescriptor {
+                name: #item_name,
+                extends: #descriptor_extends,
+                fields: &[#(#field_descriptors),*],
+            };
+
+            /// Runs the `#[dsl(validate)]` checks of the fields, those of the extended items first.
+            pub fn validate(&self) -> ::ddd_runtime::__private::Vec<::ddd_runtime::validate::Diagnostic> {
+                #[allow(unused_mut)]
+                let mut diagnostics = ::ddd_runtime::__private::Vec::new();
+                #base_validate
+                #(#validations)*
+                diagnostics
+            }
+
+            /// Names of the `#[dsl(required)]` fields that have not been set.
+            pub fn missing_required_fields(&self) -> ::ddd_runtime::__private::Vec<&'static str> {
+                #[allow(unused_mut)]
+                let mut missing = ::ddd_runtime::__private::Vec::new();
+                #base_required
+                #(#required_checks)*
+                missing
+            }
+
+            /// Names of the fields that have been set, in declaration order.
+            /// Only these are written when the item is serialized, the template
+            /// recorded by `merge_from` is not.
+            pub fn explicitly_set_fields(&self) -> ::ddd_runtime::__private::Vec<&'static str> {
+                #[allow(unused_mut)]
+                let mut set = ::ddd_runtime::__private::Vec::new();
+                #base_explicitly_set
+                #(#set_checks)*
+                set
+            }
+
+            /// The template this item was merged from, see `merge_from`. It is kept
+            /// in memory only, an item read back from YAML has none, and is ignored
+            /// by `PartialEq` and `Debug`.
+            pub fn derived_from(&self) -> ::ddd_runtime::__private::Option<&Self> {
+                self.derived_from.as_deref()
+            }
+        }
+
+        impl #impl_generics #dsl_ident #ty_generics #clone_where {
+            /// Fills every field that has not been set with the value of `other`, the
+            /// template, and records it as the item this one is derived from.
+            pub fn merge_from(&mut self, other: &Self) -> &mut Self {
+                self.merge_fields(other);
+                self.derived_from = ::ddd_runtime::__private::Option::Some(::ddd_runtime::__private::Box::new(other.clone()));
+                self
+            }
+
+            /// A copy of this item with the unset fields taken from `base`, see `merge_from`.
+            pub fn overlay(&self, base: &Self) -> Self {
+                let mut ret = self.clone();
+                ret.merge_from(base);
+                ret
+            }
+
+            #[doc(hidden)]
+            pub fn merge_fields(&mut self, other: &Self) {
+                #base_merge
+                #(#merges)*
+            }
+        }
+
+        impl #impl_generics #dsl_ident #ty_generics #source_where {
+            /// Rust builder code creating an equal item, `dslX(|o| { .. })` with a
+            /// setter call per field that has been set.
+            pub fn to_dsl_source(&self) -> ::ddd_runtime::__private::String {
+                let mut statements = ::ddd_runtime::__private::Vec::new();
+                self.dsl_source_statements(&mut statements);
+                ::ddd_runtime::__private::item_source(#builder_name, #builder_default_name, &statements)
+            }
+
+            #[doc(hidden)]
+            pub fn dsl_source_statements(&self, statements: &mut ::ddd_runtime::__private::Vec<::ddd_runtime::__private::String>) {
+                #base_source
+                #(#source_statements)*
+            }
+        }
+
+        impl #impl_generics ::ddd_runtime::source::ToDslSource for #dsl_ident #ty_generics #source_where {
+            fn to_dsl_source(&self) -> ::ddd_runtime::__private::String {
+                #dsl_ident::to_dsl_source(self)
+            }
+        }
+
+        impl #impl_generics ::ddd_runtime::reflect::Reflect for #dsl_ident #ty_generics #reflect_where {
+            fn descriptor(&self) -> &'static ::ddd_runtime::reflect::ItemDescriptor {
+                &Self::DESCRIPTOR
+            }
+
+            fn as_any(&self) -> &dyn ::ddd_runtime::__private::Any
+            where
+                Self: 'static,
+            {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::ddd_runtime::__private::Any
+            where
+                Self: 'static,
+            {
+                self
+            }
+
+            fn get_field(
+                &self,
+                name: &str,
+            ) -> ::ddd_runtime::__private::Result<::ddd_runtime::reflect::DynValue, ::ddd_runtime::reflect::ReflectError> {
+                match name {
+                    #(#get_fields)*
+                    _ => #get_other_field,
+                }
+            }
+
+            fn set_field(
+                &mut self,
+                name: &str,
+                value: ::ddd_runtime::reflect::DynValue,
+            ) -> ::ddd_runtime::__private::Result<(), ::ddd_runtime::reflect::ReflectError> {
+                match name {
+                    #(#set_fields)*
+                    _ => #set_other_field,
+                }
+            }
+
+            fn references(&self) -> ::ddd_runtime::__private::Vec<(&'static str, &dyn ::ddd_runtime::reference::AnyRef)> {
+                #[allow(unused_mut)]
+                let mut references = ::ddd_runtime::__private::Vec::new();
+                #base_references
+                #(#references)*
+                references
+            }
+        }
+
+        impl #impl_generics ::ddd_runtime::__private::fmt::Debug for #dsl_ident #ty_generics #debug_where {
+            fn fmt(&self, f: &mut ::ddd_runtime::__private::fmt::Formatter<'_>) -> ::ddd_runtime::__private::fmt::Result {
+                f.debug_struct(#dsl_ident_name)
+                    #(.field(#compared_names, &self.#compared_fields))*
+                    .finish()
+            }
+        }
 
-        #[derive(Default, Debug, Clone, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
-        pub struct #dsl_ident {
-            #(#dsl_fields),*
+        impl #impl_generics ::ddd_runtime::__private::PartialEq for #dsl_ident #ty_generics #eq_where {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& self.#compared_fields == other.#compared_fields)*
+            }
+        }
+
+        impl #impl_generics ::ddd_runtime::__private::Default for #dsl_ident #ty_generics #default_where {
+            fn default() -> Self {
+                #dsl_ident_builder_default()
+            }
         }
 
-        impl #trait_ident_set for #dsl_ident {
+        impl #impl_generics #trait_ident_set #ty_generics for #dsl_ident #ty_generics #where_clause {
+            #base_fn_mut_impl
             #(#setters)*
         }
 
-        #[typetag::serde]
-        impl #trait_ident_get for #dsl_ident {
+        #typetag_impl
+        impl #impl_generics #trait_ident_get #ty_generics for #dsl_ident #ty_generics #where_clause {
+            #base_fn_impl
             #(#getters)*
         }
 
-        pub fn #dsl_ident_builder_default() -> #dsl_ident {
+        pub fn #dsl_ident_builder_default #impl_generics () -> #dsl_ident #ty_generics #default_where {
             #dsl_ident {
-                #(#dsl_defaults),*
+                #base_default
+                #(#dsl_defaults,)*
+                derived_from: ::ddd_runtime::__private::Option::None,
+                #marker_default
             }
         }
 
-        pub fn #dsl_ident_builder(adapt: fn(o: &mut #dsl_ident)) -> #dsl_ident {
+        #[doc = #try_builder_doc]
+        pub fn #dsl_ident_try_builder #impl_generics (
+            adapt: impl FnOnce(&mut #dsl_ident #ty_generics),
+        ) -> ::ddd_runtime::__private::Result<#dsl_ident #ty_generics, ::ddd_runtime::__private::Vec<&'static str>> #default_where {
             let mut ret = #dsl_ident_builder_default();
             adapt(&mut ret);
-            ret
+            let missing = ret.missing_required_fields();
+            if !missing.is_empty() {
+                return ::ddd_runtime::__private::Result::Err(missing);
+            }
+            ::ddd_runtime::__private::Result::Ok(ret)
+        }
+
+        pub fn #dsl_ident_builder #impl_generics (
+            adapt: impl FnOnce(&mut #dsl_ident #ty_generics),
+        ) -> #dsl_ident #ty_generics #default_where {
+            match #dsl_ident_try_builder(adapt) {
+                ::ddd_runtime::__private::Result::Ok(ret) => ret,
+                ::ddd_runtime::__private::Result::Err(missing) => ::ddd_runtime::__private::panic!(
+                    "{}: required fields are not set: {}",
+                    #builder_name,
+                    missing.join(", ")
+                ),
+            }
         }
     };
     proc_macro::TokenStream::from(output)
 }
 
-fn empty_value(ty: &syn::Type) -> proc_macro2::TokenStream {
+#[proc_macro_derive(AsDslEnum, attributes(dsl))]
+pub fn derive_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    match enums::derive(&ast) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates `DslVisitor` and `DslVisitorMut` for the given items, `dsl_visitors!(Item, Attribute)`.
+/// They walk `ddd_model::node::Node` trees unless another node type is given
+/// first, `dsl_visitors!(node = crate::node::Node; Item, Attribute)`.
+#[proc_macro]
+pub fn dsl_visitors(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match visitors::expand(input.into()) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn unsupported_shape<T: quote::ToTokens>(tokens: T) -> proc_macro::TokenStream {
+    syn::Error::new_spanned(tokens, "derive(AsDslItem) only supports structs with named fields")
+        .to_compile_error()
+        .into()
+}
+
+/// The item named by `#[dsl(extends = ...)]`, with paths to its generated counterparts.
+struct Base {
+    /// Field of the extending `Dsl{Ident}Impl` that holds the base `Dsl{Base}Impl`.
+    field: syn::Ident,
+    dsl_impl: syn::Path,
+    item: syn::Path,
+    get: syn::Path,
+    set: syn::Path,
+    builder_default: syn::Path,
+    base_fn: syn::Ident,
+    base_fn_mut: syn::Ident,
+}
+
+impl Base {
+    fn new(path: &syn::Path) -> Self {
+        let ident = &path.segments.last().unwrap().ident;
+        let snake = to_snake_case(&ident.to_string());
+        let sibling = |name: syn::Ident| {
+            let mut sibling = path.clone();
+            sibling.segments.last_mut().unwrap().ident = name;
+            sibling
+        };
+
+        Base {
+            field: format_ident!("{snake}"),
+            dsl_impl: sibling(format_ident!("Dsl{ident}Impl")),
+            item: sibling(format_ident!("Dsl{ident}")),
+            get: sibling(format_ident!("Dsl{ident}Get")),
+            set: sibling(format_ident!("Dsl{ident}Set")),
+            builder_default: sibling(format_ident!("dsl{ident}Default")),
+            base_fn: format_ident!("dsl_{snake}"),
+            base_fn_mut: format_ident!("dsl_{snake}_mut"),
+        }
+    }
+}
+
+/// A setter generated for a field: `fn name(&mut self, args..) -> &mut Self { body; self }`.
+struct SetterMethod {
+    name: syn::Ident,
+    args: Vec<(syn::Ident, proc_macro2::TokenStream)>,
+    body: proc_macro2::TokenStream,
+}
+
+struct DslField<'a> {
+    /// Name of the field in the generated `Dsl{Ident}Impl`.
+    id: syn::Ident,
+    /// Name used for the getter and the setters, `#[dsl(rename)]` or the field name.
+    name: syn::Ident,
+    ty: &'a syn::Type,
+    /// Mapping of the field type, of the item type for `Vec` and of the value type
+    /// for `Option` fields. `None` for boxed values and skipped fields.
+    mapping: Option<TypeMapping>,
+    attrs: FieldAttrs,
+}
+
+impl<'a> DslField<'a> {
+    fn new(
+        field: &'a syn::Field,
+        type_params: &[&syn::Ident],
+        registry: &TypeRegistry,
+    ) -> syn::Result<Self> {
+        let attrs = FieldAttrs::parse(field)?;
+        let id = field.ident.clone().unwrap();
+        let mapping = if attrs.skip {
+            None
+        } else {
+            field_mapping(&field.ty, type_params, registry)?
+        };
+        let name = match &attrs.rename {
+            Some(rename) => rename.parse()?,
+            None => id.clone(),
+        };
+
+        let validate = &attrs.validate;
+        let is_str = |mapping: &Option<TypeMapping>| {
+            mapping.as_ref().is_some_and(|mapping| mapping.getter.to_string() == "& str")
+        };
+        if let Some(min_len) = &validate.min_len {
+            let supported = match field_kind(&field.ty) {
+                FieldKind::Vec(_) => true,
+                FieldKind::Scalar | FieldKind::Option(_) => is_str(&mapping),
+                FieldKind::Box => false,
+            };
+            if !supported {
+                return Err(syn::Error::new(
+                    min_len.span(),
+                    "min_len is only supported on string and Vec fields",
+                ));
+            }
+        }
+        if let Some(regex) = &validate.regex {
+            if matches!(field_kind(&field.ty), FieldKind::Box) || !is_str(&mapping) {
+                return Err(syn::Error::new(
+                    regex.span(),
+                    "regex is only supported on string fields",
+                ));
+            }
+        }
+        if let (Some((range, _)), FieldKind::Box) = (&validate.range, field_kind(&field.ty)) {
+            return Err(syn::Error::new(range.span(), "range is not supported on Box fields"));
+        }
+
+        if let Some(default) = &attrs.default {
+            if !matches!(field_kind(&field.ty), FieldKind::Scalar) {
+                return Err(syn::Error::new(
+                    default.span(),
+                    "dsl default is only supported on scalar fields",
+                ));
+            }
+            if mapping.as_ref().is_some_and(|mapping| mapping.from_literal.is_none()) {
+                default.parse::<syn::Expr>()?;
+            }
+        }
+
+        Ok(DslField {
+            id,
+            name,
+            ty: &field.ty,
+            mapping,
+            attrs,
+        })
+    }
+
+    fn empty_value(&self) -> proc_macro2::TokenStream {
+        let Some(mapping) = &self.mapping else {
+            return quote! { ::ddd_runtime::__private::Default::default() };
+        };
+        match (&self.attrs.default, &mapping.from_literal) {
+            (Some(default), Some(from_literal)) => quote! {{
+                let value = #default;
+                #from_literal
+            }},
+            (Some(default), None) => {
+                let expr: syn::Expr = default.parse().unwrap();
+                quote! { #expr }
+            }
+            (None, _) => mapping.empty.clone(),
+        }
+    }
+
+    /// Type stored in `Dsl{Ident}Impl` for `ty`, the field type or its item or value type.
+    fn storage(&self, ty: &syn::Type) -> proc_macro2::TokenStream {
+        match &self.mapping {
+            Some(mapping) => mapping.storage.clone(),
+            None => quote! { #ty },
+        }
+    }
+
+    fn getter_type(&self) -> proc_macro2::TokenStream {
+        let ty = self.ty;
+        match (field_kind(ty), &self.mapping) {
+            (FieldKind::Vec(item_ty), _) => {
+                let item_ty = self.storage(item_ty);
+                quote! { &[#item_ty] }
+            }
+            (FieldKind::Option(_), Some(mapping)) => {
+                let getter = &mapping.getter;
+                quote! { ::ddd_runtime::__private::Option<#getter> }
+            }
+            (FieldKind::Option(value_ty), None) => quote! { ::ddd_runtime::__private::Option<&#value_ty> },
+            (FieldKind::Scalar, Some(mapping)) => mapping.getter.clone(),
+            (FieldKind::Box | FieldKind::Scalar, _) => quote! { &::ddd_runtime::__private::Option<#ty> },
+        }
+    }
+
+    /// Argument type of a setter for `ty` and the expression converting `value` into its storage.
+    fn setter_arg(&self, ty: &syn::Type) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        match &self.mapping {
+            Some(mapping) => (mapping.setter.clone(), mapping.store(quote! { value })),
+            None => (quote! { #ty }, quote! { value }),
+        }
+    }
+
+    fn setter_methods(&self) -> Vec<SetterMethod> {
+        let id = &self.id;
+        let name = &self.name;
+        let value = format_ident!("value");
+        let values = format_ident!("values");
+        let build = format_ident!("build");
+
+        let mut methods = match field_kind(self.ty) {
+            FieldKind::Vec(item_ty) => {
+                let (add_arg, add_value) = self.setter_arg(item_ty);
+                // Borrowed arguments are taken as a slice, `extend_names(&["a", "b"])`.
+                let (extend_arg, extend_values) = match &self.mapping {
+                    Some(mapping @ TypeMapping { into: Some(_), .. }) => {
+                        let setter = &mapping.setter;
+                        if matches!(syn::parse2(setter.clone()), Ok(syn::Type::Reference(_))) {
+                            let value = mapping.store(quote! { *value });
+                            (quote! { &[#setter] }, quote! { values.iter().map(|value| #value) })
+                        } else {
+                            let value = mapping.store(quote! { value });
+                            (
+                                quote! { ::ddd_runtime::__private::Vec<#setter> },
+                                quote! { values.into_iter().map(|value| #value) },
+                            )
+                        }
+                    }
+                    _ => {
+                        let item_ty = self.storage(item_ty);
+                        (quote! { ::ddd_runtime::__private::Vec<#item_ty> }, quote! { values })
+                    }
+                };
+
+                let mut methods = vec![
+                    SetterMethod {
+                        name: format_ident!("add_{name}"),
+                        args: vec![(value, add_arg)],
+                        body: quote! { self.#id.push(#add_value); },
+                    },
+                    SetterMethod {
+                        name: format_ident!("extend_{name}"),
+                        args: vec![(values, extend_arg)],
+                        body: quote! { self.#id.extend(#extend_values); },
+                    },
+                    SetterMethod {
+                        name: format_ident!("clear_{name}"),
+                        args: vec![],
+                        body: quote! { self.#id.clear(); },
+                    },
+                ];
+                if let Some((child_ty, build_child)) = child_builder(item_ty) {
+                    methods.push(SetterMethod {
+                        name: format_ident!("add_{name}_with"),
+                        args: vec![(build, quote! { impl FnOnce(&mut #child_ty) })],
+                        body: quote! { self.#id.push(#build_child); },
+                    });
+                }
+                methods
+            }
+            FieldKind::Option(value_ty) => {
+                let (arg, set_value) = self.setter_arg(value_ty);
+
+                let mut methods = vec![
+                    SetterMethod {
+                        name: name.clone(),
+                        args: vec![(value, arg)],
+                        body: quote! { self.#id = ::ddd_runtime::__private::Option::Some(#set_value); },
+                    },
+                    SetterMethod {
+                        name: format_ident!("unset_{name}"),
+                        args: vec![],
+                        body: quote! { self.#id = ::ddd_runtime::__private::Option::None; },
+                    },
+                ];
+                methods.extend(self.child_setter(value_ty, build));
+                methods
+            }
+            FieldKind::Box | FieldKind::Scalar => {
+                let (arg, set_value) = self.setter_arg(self.ty);
+
+                let mut methods = vec![SetterMethod {
+                    name: name.clone(),
+                    args: vec![(value, arg)],
+                    body: quote! { self.#id = ::ddd_runtime::__private::Option::Some(#set_value); },
+                }];
+                methods.extend(self.child_setter(self.ty, build));
+                methods
+            }
+        };
+
+        let reset = match field_kind(self.ty) {
+            FieldKind::Vec(_) => quote! { self.#id.clear(); },
+            _ => quote! { self.#id = ::ddd_runtime::__private::Option::None; },
+        };
+        methods.push(SetterMethod {
+            name: format_ident!("reset_{name}"),
+            args: vec![],
+            body: reset,
+        });
+        methods
+    }
+
+    /// Whether the field has been set, a `Vec` field counts as set once it has items.
+    fn is_set(&self) -> proc_macro2::TokenStream {
+        let is_unset = self.is_unset();
+        quote! { !#is_unset }
+    }
+
+    fn is_unset(&self) -> proc_macro2::TokenStream {
+        let id = &self.id;
+        match field_kind(self.ty) {
+            FieldKind::Vec(_) => quote! { self.#id.is_empty() },
+            _ => quote! { self.#id.is_none() },
+        }
+    }
+
+    /// `x_with(build)` for a single child item, built by `dsl{Child}(build)`.
+    fn child_setter(&self, ty: &syn::Type, build: syn::Ident) -> Option<SetterMethod> {
+        let id = &self.id;
+        let (child_ty, build_child) = child_builder(ty)?;
+
+        Some(SetterMethod {
+            name: format_ident!("{}_with", self.name),
+            args: vec![(build, quote! { impl FnOnce(&mut #child_ty) })],
+            body: quote! { self.#id = ::ddd_runtime::__private::Option::Some(#build_child); },
+        })
+    }
+
+    /// The `#[dsl(validate)]` checks of the field, pushing to `diagnostics`. `value` is
+    /// the getter value; `Option` fields are checked when set, the items of `Vec` fields
+    /// one by one, except for `min_len` and custom functions, which get the whole list.
+    fn validation(&self, item: &str, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let validate = &self.attrs.validate;
+        if validate.is_empty() {
+            return quote! {};
+        }
+        let name = self.name.to_string();
+        let push = |message: proc_macro2::TokenStream| {
+            quote! {
+                diagnostics.push(::ddd_runtime::validate::Diagnostic::new(#item, #name, #message));
+            }
+        };
+
+        let mut value_checks = Vec::new();
+        if let Some(regex) = &validate.regex {
+            let message = push(format!("must match `{}`", regex.value()).into_token_stream());
+            value_checks.push(quote! {
+                if !::ddd_runtime::__private::is_match(#regex, value) {
+                    #message
+                }
+            });
+        }
+        if let Some((range, expr)) = &validate.range {
+            let message = push(format!("must be in {}", range.value()).into_token_stream());
+            value_checks.push(quote! {
+                if !(#expr).contains(value) {
+                    #message
+                }
+            });
+        }
+        let custom_checks = validate.custom.iter().map(|custom| {
+            let message = push(quote! { message });
+            quote! {
+                if let ::ddd_runtime::__private::Result::Err(message) = #custom(#value) {
+                    #message
+                }
+            }
+        });
+
+        let min_len = validate.min_len.as_ref().map(|min_len| {
+            let plural = if min_len.base10_digits() == "1" { "" } else { "s" };
+            let (len, message) = match field_kind(self.ty) {
+                FieldKind::Vec(_) => (
+                    quote! { value.len() },
+                    format!("must have at least {min_len} item{plural}"),
+                ),
+                _ => (
+                    quote! { value.chars().count() },
+                    format!("must be at least {min_len} character{plural} long"),
+                ),
+            };
+            let message = push(message.into_token_stream());
+            quote! {
+                if #len < #min_len {
+                    #message
+                }
+            }
+        });
+
+        if min_len.is_none() && value_checks.is_empty() {
+            return quote! { #(#custom_checks)* };
+        }
+        let checks = match field_kind(self.ty) {
+            FieldKind::Vec(_) if value_checks.is_empty() => quote! {
+                let value = #value;
+                #min_len
+            },
+            FieldKind::Vec(_) => quote! {
+                let value = #value;
+                #min_len
+                for value in value {
+                    #(#value_checks)*
+                }
+            },
+            FieldKind::Option(_) => quote! {
+                if let ::ddd_runtime::__private::Option::Some(value) = #value {
+                    #min_len
+                    #(#value_checks)*
+                }
+            },
+            FieldKind::Scalar | FieldKind::Box => quote! {
+                let value = #value;
+                #min_len
+                #(#value_checks)*
+            },
+        };
+        quote! {
+            {
+                #checks
+            }
+            #(#custom_checks)*
+        }
+    }
+
+    fn doc(&self) -> Option<proc_macro2::TokenStream> {
+        self.attrs.doc.as_ref().map(|doc| quote! { #[doc = #doc] })
+    }
+}
+
+/// For `Dsl{Child}Impl` or `Box<Dsl{Child}Impl>` the child type and an expression
+/// building a value of `ty` with `dsl{Child}(build)`, the builder next to the child type.
+fn child_builder(ty: &syn::Type) -> Option<(&syn::Type, proc_macro2::TokenStream)> {
+    if let Some(child_ty) = generic_type_arg(ty, "Box") {
+        let (child_ty, build_child) = child_builder(child_ty)?;
+        return Some((child_ty, quote! { ::ddd_runtime::__private::Box::new(#build_child) }));
+    }
+
+    let syn::Type::Path(child_path) = ty else {
+        return None;
+    };
+    let child = dsl_impl_item(ty)?;
+    let mut builder = child_path.path.clone();
+    builder.segments.last_mut().unwrap().ident = format_ident!("dsl{child}");
+    Some((ty, quote! { #builder(build) }))
+}
+
+/// `Child` for a generated `Dsl{Child}Impl` type.
+fn dsl_impl_item(ty: &syn::Type) -> Option<String> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if !segment.arguments.is_empty() {
+        return None;
+    }
+    let ident = segment.ident.to_string();
+    let child = ident.strip_prefix("Dsl")?.strip_suffix("Impl")?;
+    (!child.is_empty()).then(|| child.to_owned())
+}
+
+/// Brings `typetag` into scope for the expansion of `#[typetag::serde]`, which refers to
+/// `typetag::...` rather than to an absolute path. Repeated glob imports don't conflict.
+fn typetag_scope() -> proc_macro2::TokenStream {
+    quote! {
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        use ::ddd_runtime::__private::typetag_scope::*;
+    }
+}
+
+/// `ty` as it is usually written, `Vec<String>` rather than `Vec < String >`.
+fn type_string(ty: &syn::Type) -> String {
+    let mut string = quote! { #ty }.to_string();
+    for (spaced, tight) in [(" <", "<"), ("< ", "<"), (" >", ">"), (" ::", "::"), (":: ", "::"), (" ,", ","), ("& ", "&")] {
+        string = string.replace(spaced, tight);
+    }
+    string
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+enum FieldKind<'a> {
+    Scalar,
+    Box,
+    Vec(&'a syn::Type),
+    Option(&'a syn::Type),
+}
+
+fn field_kind(ty: &syn::Type) -> FieldKind<'_> {
+    if let Some(item_ty) = generic_type_arg(ty, "Vec") {
+        FieldKind::Vec(item_ty)
+    } else if let Some(value_ty) = generic_type_arg(ty, "Option") {
+        FieldKind::Option(value_ty)
+    } else if is_box_type(ty) {
+        FieldKind::Box
+    } else {
+        FieldKind::Scalar
+    }
+}
+
+/// The mapping of a field type, see `DslField::mapping`.
+fn field_mapping(
+    ty: &syn::Type,
+    type_params: &[&syn::Ident],
+    registry: &TypeRegistry,
+) -> syn::Result<Option<TypeMapping>> {
+    match field_kind(ty) {
+        FieldKind::Vec(inner_ty) | FieldKind::Option(inner_ty) => match field_kind(inner_ty) {
+            FieldKind::Scalar => scalar_mapping(inner_ty, type_params, registry).map(Some),
+            FieldKind::Box => Ok(None),
+            FieldKind::Vec(_) | FieldKind::Option(_) => Err(syn::Error::new_spanned(
+                inner_ty,
+                "derive(AsDslItem) doesn't support nested Vec and Option fields",
+            )),
+        },
+        FieldKind::Box => Ok(None),
+        FieldKind::Scalar => scalar_mapping(ty, type_params, registry).map(Some),
+    }
+}
+
+fn scalar_mapping(
+    ty: &syn::Type,
+    type_params: &[&syn::Ident],
+    registry: &TypeRegistry,
+) -> syn::Result<TypeMapping> {
+    if let Some(mapping) = registry.lookup(ty) {
+        return Ok(mapping);
+    }
+    if dsl_impl_item(ty).is_some() || is_type_param(ty, type_params) {
+        return Ok(TypeMapping::by_ref(ty));
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        "unsupported field type for derive(AsDslItem), use String, &'static str, PathBuf, \
+         bool, char, a number type, a Dsl*Impl item, Ref<T>, Option<T>, Vec<T>, Box<T> \
+         or map it with #[dsl(type_map(...))]",
+    ))
+}
+
+fn is_type_param(ty: &syn::Type, type_params: &[&syn::Ident]) -> bool {
     match ty {
-        syn::Type::Path(path) => {
-            let path_str = quote::quote! {#path}.to_string();
-            match path_str.as_str() {
-                "String" => quote! { String::new() },
-                "bool" => quote! { false },
-                "i8" => quote! { 0i8 },
-                "u8" => quote! { 0u8 },
-                "i16" => quote! { 0i16 },
-                "u16" => quote! { 0u16 },
-                "i32" => quote! { 0i32 },
-                "u32" => quote! { 0u32 },
-                "i64" => quote! { 0i64 },
-                "u64" => quote! { 0u64 },
-                "i128" => quote! { 0i128 },
-                "u128" => quote! { 0u128 },
-                "isize" => quote! { 0isize },
-                "usize" => quote! { 0usize },
-                "f32" => quote! { 0.0f32 },
-                "f64" => quote! { 0.0f64 },
-                _ => quote! { std::default::Default::default() },
-            }
-        }
-        _ => quote! { std::default::Default::default() },
+        syn::Type::Path(path) if path.qself.is_none() => path
+            .path
+            .get_ident()
+            .is_some_and(|ident| type_params.contains(&ident)),
+        _ => false,
     }
 }
 
 fn is_box_type(ty: &syn::Type) -> bool {
     if let syn::Type::Path(path) = ty {
         if let Some(segment) = path.path.segments.last() {
-            if segment.ident.to_string() == "Box" {
+            if segment.ident == "Box" {
                 return true;
             }
         }
@@ -222,43 +1448,17 @@ fn is_box_type(ty: &syn::Type) -> bool {
     false
 }
 
-fn dsl_type_ref(ty: &syn::Type) -> proc_macro2::TokenStream {
-    match ty {
-        syn::Type::Path(path) => {
-            let path_str = quote! {#path}.to_string();
-            match path_str.as_str() {
-                "String" => quote! { &str },
-                "bool" => quote! { &bool },
-                "i8" => quote! { &i8 },
-                "u8" => quote! { &u8 },
-                "i16" => quote! { &i16 },
-                "u16" => quote! { &u16 },
-                "i32" => quote! { &i13 },
-                "u32" => quote! { &u32 },
-                "i64" => quote! { &i64 },
-                "u64" => quote! { &i64 },
-                "i128" => quote! { &i128 },
-                "u128" => quote! { &&u128 },
-                "isize" => quote! { &isize },
-                "usize" => quote! { &usize },
-                "f32" => quote! { &f32 },
-                "f64" => quote! { &f64 },
-                _ => quote! { &std::option::Option<#ty> },
-            }
-        }
-        _ => quote! { &#ty },
-    }
-}
-
-fn is_string(ty: &syn::Type) -> bool {
-    match ty {
-        syn::Type::Path(path) => {
-            let path_str = quote! {#path}.to_string();
-            match path_str.as_str() {
-                "String" => true,
-                _ => false,
+fn generic_type_arg<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
+    if let syn::Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == wrapper {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(arg_ty)) = args.args.first() {
+                        return Some(arg_ty);
+                    }
+                }
             }
         }
-        _ => false,
     }
+    None
 }
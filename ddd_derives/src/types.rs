@@ -0,0 +1,242 @@
+use crate::attrs::TypeMapAttrs;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+/// How a field type is stored in `Dsl{Ident}Impl` and exposed by its getter and setters.
+///
+/// `Vec<T>` and `Option<T>` fields use the mapping of `T` for their items and values.
+#[derive(Clone)]
+pub struct TypeMapping {
+    /// Type stored in `Dsl{Ident}Impl`.
+    pub storage: TokenStream,
+    /// Type returned by the getter.
+    pub getter: TokenStream,
+    /// Expression turning `value: &storage` into the getter type, `None` if `&storage` is returned as is.
+    pub borrow: Option<TokenStream>,
+    /// Value returned by the getter while the field is unset.
+    pub empty: TokenStream,
+    /// Argument type of the setter.
+    pub setter: TokenStream,
+    /// Expression turning the setter argument `value` into the storage type,
+    /// `None` if the argument is stored as is.
+    pub into: Option<TokenStream>,
+    /// Expression turning `value: &'static str` into the storage type. `#[dsl(default)]`
+    /// literals go through it, types without it parse the literal as an expression.
+    pub from_literal: Option<TokenStream>,
+    /// Expression turning `value: &storage` into the Rust source of a setter argument.
+    pub source: TokenStream,
+    /// Whether the type is a `Ref<T>`, listed by `Reflect::references`.
+    pub reference: bool,
+}
+
+impl TypeMapping {
+    /// Stores `ty` as is, hands out `&ty` and starts from `Default::default()`.
+    pub fn by_ref(ty: &syn::Type) -> Self {
+        TypeMapping {
+            storage: quote! { #ty },
+            getter: quote! { &#ty },
+            borrow: None,
+            empty: quote! { ::ddd_runtime::__private::Default::default() },
+            setter: quote! { #ty },
+            into: None,
+            from_literal: None,
+            source: quote! { ::ddd_runtime::source::ToDslSource::to_dsl_arg(value) },
+            reference: false,
+        }
+    }
+
+    fn with_empty(ty: &syn::Type, empty: TokenStream) -> Self {
+        TypeMapping {
+            empty,
+            ..TypeMapping::by_ref(ty)
+        }
+    }
+
+    /// The getter value for `value`, an expression of type `&storage`.
+    pub fn borrow(&self, value: TokenStream) -> TokenStream {
+        match &self.borrow {
+            Some(borrow) => quote! {{
+                let value = #value;
+                #borrow
+            }},
+            None => value,
+        }
+    }
+
+    /// The stored value for `value`, an expression of the setter type.
+    pub fn store(&self, value: TokenStream) -> TokenStream {
+        match &self.into {
+            Some(into) => quote! {{
+                let value = #value;
+                #into
+            }},
+            None => value,
+        }
+    }
+}
+
+/// Field types known to `derive(AsDslItem)`, the built-in ones and those added
+/// with `#[dsl(type_map(...))]`.
+pub struct TypeRegistry {
+    user: Vec<(String, TypeMapping)>,
+}
+
+impl TypeRegistry {
+    pub fn new(type_maps: &[TypeMapAttrs]) -> Self {
+        let user = type_maps
+            .iter()
+            .map(|type_map| {
+                let ty = &type_map.ty;
+                let default = TypeMapping::by_ref(ty);
+                let mapping = TypeMapping {
+                    getter: type_map.getter.as_ref().map_or(default.getter, ToTokens::to_token_stream),
+                    borrow: type_map.borrow.as_ref().map(ToTokens::to_token_stream),
+                    empty: type_map.default.as_ref().map_or(default.empty, ToTokens::to_token_stream),
+                    setter: type_map.setter.as_ref().map_or(default.setter, ToTokens::to_token_stream),
+                    into: type_map.into.as_ref().map(ToTokens::to_token_stream),
+                    source: match &type_map.source {
+                        Some(source) => source.to_token_stream(),
+                        None => quote! { ::ddd_runtime::__private::format!("{:?}", value) },
+                    },
+                    ..default
+                };
+                (ty.to_token_stream().to_string(), mapping)
+            })
+            .collect();
+        TypeRegistry { user }
+    }
+
+    /// The mapping of `ty`, `None` if `ty` is neither built in nor mapped by the user.
+    pub fn lookup(&self, ty: &syn::Type) -> Option<TypeMapping> {
+        let name = ty.to_token_stream().to_string();
+        match self.user.iter().find(|(user_ty, _)| *user_ty == name) {
+            Some((_, mapping)) => Some(mapping.clone()),
+            None => builtin(ty),
+        }
+    }
+}
+
+fn builtin(ty: &syn::Type) -> Option<TypeMapping> {
+    if let Some(target) = ref_target(ty) {
+        return Some(TypeMapping {
+            storage: quote! { ::ddd_runtime::reference::Ref<#target> },
+            getter: quote! { &::ddd_runtime::reference::Ref<#target> },
+            borrow: None,
+            empty: quote! { ::ddd_runtime::__private::Default::default() },
+            setter: quote! { &str },
+            into: Some(quote! { ::ddd_runtime::reference::Ref::new(value) }),
+            from_literal: Some(quote! { ::ddd_runtime::reference::Ref::new(value) }),
+            source: quote! { ::ddd_runtime::source::ToDslSource::to_dsl_arg(value) },
+            reference: true,
+        });
+    }
+
+    let name = type_name(ty)?;
+    let name = match name.as_str() {
+        "std::string::String" | "alloc::string::String" => "String",
+        "std::path::PathBuf" => "PathBuf",
+        name => name
+            .strip_prefix("std::primitive::")
+            .or_else(|| name.strip_prefix("core::primitive::"))
+            .unwrap_or(name),
+    };
+
+    let mapping = match name {
+        "String" => TypeMapping {
+            storage: quote! { #ty },
+            getter: quote! { &str },
+            borrow: Some(quote! { value.as_str() }),
+            empty: quote! { ::ddd_runtime::__private::String::new() },
+            setter: quote! { &str },
+            into: Some(quote! { value.to_owned() }),
+            from_literal: Some(quote! { ::ddd_runtime::__private::String::from(value) }),
+            source: quote! { ::ddd_runtime::source::ToDslSource::to_dsl_arg(value) },
+            reference: false,
+        },
+        // Deserializing can't hand out `'static` borrows, values read back are owned.
+        "&'static str" => TypeMapping {
+            storage: quote! { ::ddd_runtime::__private::Cow<'static, str> },
+            getter: quote! { &str },
+            borrow: Some(quote! { &**value }),
+            empty: quote! { ::ddd_runtime::__private::Cow::Borrowed("") },
+            setter: quote! { &'static str },
+            into: Some(quote! { ::ddd_runtime::__private::Cow::Borrowed(value) }),
+            from_literal: Some(quote! { ::ddd_runtime::__private::Cow::Borrowed(value) }),
+            source: quote! { ::ddd_runtime::source::ToDslSource::to_dsl_arg(value) },
+            reference: false,
+        },
+        "PathBuf" => TypeMapping {
+            storage: quote! { #ty },
+            getter: quote! { &::ddd_runtime::__private::Path },
+            borrow: Some(quote! { value.as_path() }),
+            empty: quote! { ::ddd_runtime::__private::PathBuf::new() },
+            setter: quote! { &::ddd_runtime::__private::Path },
+            into: Some(quote! { value.to_path_buf() }),
+            from_literal: Some(quote! { ::ddd_runtime::__private::PathBuf::from(value) }),
+            source: quote! { ::ddd_runtime::source::ToDslSource::to_dsl_arg(value) },
+            reference: false,
+        },
+        "bool" => TypeMapping::with_empty(ty, quote! { false }),
+        "char" => TypeMapping::with_empty(ty, quote! { '\0' }),
+        "i8" => TypeMapping::with_empty(ty, quote! { 0i8 }),
+        "u8" => TypeMapping::with_empty(ty, quote! { 0u8 }),
+        "i16" => TypeMapping::with_empty(ty, quote! { 0i16 }),
+        "u16" => TypeMapping::with_empty(ty, quote! { 0u16 }),
+        "i32" => TypeMapping::with_empty(ty, quote! { 0i32 }),
+        "u32" => TypeMapping::with_empty(ty, quote! { 0u32 }),
+        "i64" => TypeMapping::with_empty(ty, quote! { 0i64 }),
+        "u64" => TypeMapping::with_empty(ty, quote! { 0u64 }),
+        "i128" => TypeMapping::with_empty(ty, quote! { 0i128 }),
+        "u128" => TypeMapping::with_empty(ty, quote! { 0u128 }),
+        "isize" => TypeMapping::with_empty(ty, quote! { 0isize }),
+        "usize" => TypeMapping::with_empty(ty, quote! { 0usize }),
+        "f32" => TypeMapping::with_empty(ty, quote! { 0.0f32 }),
+        "f64" => TypeMapping::with_empty(ty, quote! { 0.0f64 }),
+        _ => return None,
+    };
+    Some(mapping)
+}
+
+/// `T` for `Ref<T>`, also written as `ddd_runtime::reference::Ref<T>`.
+fn ref_target(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if path.qself.is_some() || segment.ident != "Ref" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(target)) if args.args.len() == 1 => Some(target),
+        _ => None,
+    }
+}
+
+/// `a::b::C` for a plain path type, `&'static str` for a static string slice.
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(path) if path.qself.is_none() => {
+            let segments = &path.path.segments;
+            if segments.iter().any(|segment| !segment.arguments.is_empty()) {
+                return None;
+            }
+            let idents = segments.iter().map(|segment| segment.ident.to_string());
+            Some(idents.collect::<Vec<_>>().join("::"))
+        }
+        syn::Type::Reference(reference) if reference.mutability.is_none() => {
+            let is_static = reference
+                .lifetime
+                .as_ref()
+                .is_some_and(|lifetime| lifetime.ident == "static");
+            let is_str = matches!(
+                &*reference.elem,
+                syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("str")
+            );
+            (is_static && is_str).then(|| "&'static str".to_owned())
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,165 @@
+use crate::to_snake_case;
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+
+/// An item kind passed to `dsl_visitors!`, with the path to its `Dsl{Ident}Impl`.
+struct Kind {
+    dsl_impl: syn::Path,
+    visit: syn::Ident,
+    visit_mut: syn::Ident,
+}
+
+impl Kind {
+    fn new(path: &syn::Path) -> Self {
+        let ident = &path.segments.last().unwrap().ident;
+        let snake = to_snake_case(&ident.to_string());
+        let mut dsl_impl = path.clone();
+        dsl_impl.segments.last_mut().unwrap().ident = format_ident!("Dsl{ident}Impl");
+
+        Kind {
+            dsl_impl,
+            visit: format_ident!("visit_{snake}"),
+            visit_mut: format_ident!("visit_{snake}_mut"),
+        }
+    }
+}
+
+/// The input of `dsl_visitors!`: the items to visit, optionally preceded by
+/// the path of the node type, `node = crate::node::Node;`.
+struct Input {
+    node: syn::Path,
+    items: Punctuated<syn::Path, syn::Token![,]>,
+}
+
+impl syn::parse::Parse for Input {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let node = if input.peek(syn::Ident) && input.peek2(syn::Token![=]) {
+            let key = input.parse::<syn::Ident>()?;
+            if key != "node" {
+                return Err(syn::Error::new_spanned(key, "expected `node = path::to::Node;`"));
+            }
+            input.parse::<syn::Token![=]>()?;
+            let node = input.parse()?;
+            input.parse::<syn::Token![;]>()?;
+            node
+        } else {
+            syn::parse_quote! { ::ddd_model::node::Node }
+        };
+        Ok(Input {
+            node,
+            items: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+pub fn expand(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let Input { node: node_ty, items } = syn::parse2(input)?;
+    if items.is_empty() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "dsl_visitors! needs the items to visit, `dsl_visitors!(Item, Attribute)`",
+        ));
+    }
+    let kinds = items.iter().map(Kind::new).collect::<Vec<_>>();
+    let node_ref = quote! { ::ddd_runtime::__private::Rc<::ddd_runtime::__private::RefCell<#node_ty>> };
+
+    let visit_defs = kinds.iter().map(|Kind { dsl_impl, visit, .. }| {
+        quote! {
+            fn #visit(&mut self, node: &#node_ty, item: &#dsl_impl) {
+                let _ = item;
+                walk_children(self, node);
+            }
+        }
+    });
+    let visit_mut_defs = kinds.iter().map(|Kind { dsl_impl, visit_mut, .. }| {
+        quote! {
+            fn #visit_mut(&mut self, node: &#node_ref, item: &mut #dsl_impl) {
+                let _ = item;
+                walk_children_mut(self, node);
+            }
+        }
+    });
+    let dispatch = kinds.iter().map(|Kind { dsl_impl, visit, .. }| {
+        quote! {
+            if let ::ddd_runtime::__private::Option::Some(item) = item.downcast_ref::<#dsl_impl>() {
+                return visitor.#visit(node, item);
+            }
+        }
+    });
+    let dispatch_mut = kinds.iter().map(|Kind { dsl_impl, visit_mut, .. }| {
+        quote! {
+            if let ::ddd_runtime::__private::Option::Some(edited) =
+                ::ddd_runtime::reflect::Reflect::as_any_mut(&mut *item).downcast_mut::<#dsl_impl>()
+            {
+                visitor.#visit_mut(node, edited);
+                node.borrow_mut().replace_item(item);
+                return;
+            }
+        }
+    });
+
+    Ok(quote! {
+        /// Walks a `Node` tree with one `visit_<kind>` method per item kind.
+        /// The default methods visit the children of the node.
+        pub trait DslVisitor {
+            /// Calls the `visit_<kind>` method matching the item of `node`.
+            fn visit_node(&mut self, node: &#node_ty) {
+                dispatch_node(self, node);
+            }
+
+            #(#visit_defs)*
+
+            /// Called for items of kinds this visitor doesn't know.
+            fn visit_other(&mut self, node: &#node_ty) {
+                walk_children(self, node);
+            }
+        }
+
+        /// Like `DslVisitor`, with mutable access to the items.
+        ///
+        /// No node is borrowed while a `visit_<kind>_mut` method runs: it edits a
+        /// copy of the item that is put back into `node` afterwards, so `node`
+        /// can resolve paths, walk its ancestors or have its children edited.
+        /// Changes to the item made through `node` are overwritten by the copy.
+        pub trait DslVisitorMut {
+            /// Calls the `visit_<kind>_mut` method matching the item of `node`.
+            fn visit_node_mut(&mut self, node: &#node_ref) {
+                dispatch_node_mut(self, node);
+            }
+
+            #(#visit_mut_defs)*
+
+            /// Called for items of kinds this visitor doesn't know.
+            fn visit_other_mut(&mut self, node: &#node_ref) {
+                walk_children_mut(self, node);
+            }
+        }
+
+        pub fn dispatch_node<V: DslVisitor + ?Sized>(visitor: &mut V, node: &#node_ty) {
+            let item = ::ddd_runtime::reflect::Reflect::as_any(node.item());
+            #(#dispatch)*
+            visitor.visit_other(node);
+        }
+
+        pub fn dispatch_node_mut<V: DslVisitorMut + ?Sized>(visitor: &mut V, node: &#node_ref) {
+            let mut item = ::ddd_runtime::__private::dyn_clone::clone_box(node.borrow().item());
+            #(#dispatch_mut)*
+            visitor.visit_other_mut(node);
+        }
+
+        pub fn walk_children<V: DslVisitor + ?Sized>(visitor: &mut V, node: &#node_ty) {
+            for child in node.children() {
+                visitor.visit_node(&child.borrow());
+            }
+        }
+
+        /// Visits the children `node` has when it is called, none of them is
+        /// borrowed while it is visited.
+        pub fn walk_children_mut<V: DslVisitorMut + ?Sized>(visitor: &mut V, node: &#node_ref) {
+            let children = node.borrow().children().to_vec();
+            for child in &children {
+                visitor.visit_node_mut(child);
+            }
+        }
+    })
+}
@@ -0,0 +1,9 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+enum Visibility {
+    Public,
+    Private,
+}
+
+fn main() {}
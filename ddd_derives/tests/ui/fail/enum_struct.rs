@@ -0,0 +1,8 @@
+use ddd_derives::AsDslEnum;
+
+#[derive(AsDslEnum)]
+struct Visibility {
+    name: String,
+}
+
+fn main() {}
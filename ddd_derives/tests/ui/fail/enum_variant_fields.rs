@@ -0,0 +1,9 @@
+use ddd_derives::AsDslEnum;
+
+#[derive(AsDslEnum)]
+enum Visibility {
+    Public,
+    Custom(String),
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Item {
+    name: String,
+}
+
+#[derive(AsDslItem)]
+#[dsl(extends = Item)]
+struct Constant<T> {
+    value: T,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Entity {
+    rows: Vec<Vec<u8>>,
+}
+
+fn main() {}
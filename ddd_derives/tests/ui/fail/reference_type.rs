@@ -0,0 +1,8 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Entity<'a> {
+    name: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Entity {
+    name: String,
+    derived_from: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use ddd_derives::AsDslItem;
+use std::net::Ipv4Addr;
+
+#[derive(AsDslItem)]
+#[dsl(type_map(ty = Ipv4Addr, setter = &str))]
+struct Host {
+    address: Ipv4Addr,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+union Number {
+    int: i32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Marker;
+
+fn main() {}
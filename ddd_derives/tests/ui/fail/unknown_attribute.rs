@@ -0,0 +1,9 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Entity {
+    #[dsl(hidden)]
+    name: String,
+}
+
+fn main() {}
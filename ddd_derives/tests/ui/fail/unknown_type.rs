@@ -0,0 +1,9 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Entity {
+    name: String,
+    props: std::collections::HashMap<String, String>,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use ddd_derives::AsDslItem;
+
+#[derive(AsDslItem)]
+struct Column {
+    #[dsl(validate(min_len = 1))]
+    size: u32,
+}
+
+#[derive(AsDslItem)]
+struct Table {
+    #[dsl(validate(regex = "[a-z"))]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,3 @@
+ddd_derives::dsl_visitors!();
+
+fn main() {}
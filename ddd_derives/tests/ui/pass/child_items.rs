@@ -0,0 +1,36 @@
+use ddd_derives::AsDslItem;
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Leaf {
+    name: String,
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Tree {
+    root: DslLeafImpl,
+    boxed: Box<DslLeafImpl>,
+    first: Option<DslLeafImpl>,
+    leaves: Vec<DslLeafImpl>,
+    boxed_leaves: Vec<Box<DslLeafImpl>>,
+}
+
+fn main() {
+    let name = String::from("leaf");
+    let tree = dslTree(|o| {
+        o.root_with(|l| {
+            l.name(&name);
+        })
+        .boxed_with(|_| {})
+        .first_with(|_| {})
+        .add_leaves_with(|_| {})
+        .add_boxed_leaves_with(|_| {});
+    });
+
+    let _: &DslLeafImpl = tree.root_get();
+    let _: &Option<Box<DslLeafImpl>> = tree.boxed_get();
+    let _: Option<&DslLeafImpl> = tree.first_get();
+    let _: &[DslLeafImpl] = tree.leaves_get();
+    let _: &[Box<DslLeafImpl>] = tree.boxed_leaves_get();
+}
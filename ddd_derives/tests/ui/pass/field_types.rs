@@ -0,0 +1,110 @@
+use ddd_derives::AsDslItem;
+use ddd_runtime::reference::Ref;
+use std::path::{Path, PathBuf};
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Primitives {
+    string: String,
+    boolean: bool,
+    int8: i8,
+    uint8: u8,
+    int16: i16,
+    uint16: u16,
+    int32: i32,
+    uint32: u32,
+    int64: i64,
+    uint64: u64,
+    int128: i128,
+    uint128: u128,
+    int_size: isize,
+    uint_size: usize,
+    float32: f32,
+    float64: f64,
+    character: char,
+    static_str: &'static str,
+    path: PathBuf,
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Qualified {
+    string: std::string::String,
+    uint8: core::primitive::u8,
+    float64: std::primitive::f64,
+    path: std::path::PathBuf,
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Wrappers {
+    boxed: Box<String>,
+    names: Vec<String>,
+    numbers: Vec<u64>,
+    boxes: Vec<Box<String>>,
+    alias: Option<String>,
+    length: Option<u128>,
+    boxed_alias: Option<Box<String>>,
+    labels: Vec<&'static str>,
+    paths: Vec<PathBuf>,
+    initial: Option<char>,
+    dir: Option<PathBuf>,
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct References {
+    primitives: Ref<DslPrimitivesImpl>,
+    qualified: ddd_runtime::reference::Ref<DslQualifiedImpl>,
+    wrappers: Vec<Ref<DslWrappersImpl>>,
+    optional: Option<Ref<DslPrimitivesImpl>>,
+}
+
+fn main() {
+    let o = dslPrimitivesDefault();
+    let _: &str = o.string_get();
+    let _: &bool = o.boolean_get();
+    let _: &i8 = o.int8_get();
+    let _: &u8 = o.uint8_get();
+    let _: &i16 = o.int16_get();
+    let _: &u16 = o.uint16_get();
+    let _: &i32 = o.int32_get();
+    let _: &u32 = o.uint32_get();
+    let _: &i64 = o.int64_get();
+    let _: &u64 = o.uint64_get();
+    let _: &i128 = o.int128_get();
+    let _: &u128 = o.uint128_get();
+    let _: &isize = o.int_size_get();
+    let _: &usize = o.uint_size_get();
+    let _: &f32 = o.float32_get();
+    let _: &f64 = o.float64_get();
+    let _: &char = o.character_get();
+    let _: &str = o.static_str_get();
+    let _: &Path = o.path_get();
+
+    let o = dslQualifiedDefault();
+    let _: &str = o.string_get();
+    let _: &u8 = o.uint8_get();
+    let _: &f64 = o.float64_get();
+    let _: &Path = o.path_get();
+
+    let o = dslWrappersDefault();
+    let _: &Option<Box<String>> = o.boxed_get();
+    let _: &[String] = o.names_get();
+    let _: &[u64] = o.numbers_get();
+    let _: &[Box<String>] = o.boxes_get();
+    let _: Option<&str> = o.alias_get();
+    let _: Option<&u128> = o.length_get();
+    let _: Option<&Box<String>> = o.boxed_alias_get();
+    let _: &[std::borrow::Cow<'static, str>] = o.labels_get();
+    let _: &[PathBuf] = o.paths_get();
+    let _: Option<&char> = o.initial_get();
+    let _: Option<&Path> = o.dir_get();
+
+    let mut o = dslReferencesDefault();
+    o.primitives("model.Primitives").add_wrappers("model.Wrappers").optional("model.Primitives");
+    let _: &Ref<DslPrimitivesImpl> = o.primitives_get();
+    let _: &Ref<DslQualifiedImpl> = o.qualified_get();
+    let _: &[Ref<DslWrappersImpl>] = o.wrappers_get();
+    let _: Option<&Ref<DslPrimitivesImpl>> = o.optional_get();
+}
@@ -0,0 +1,28 @@
+use ddd_derives::AsDslItem;
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Part {
+    name: String,
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Assembly {
+    main: Box<dyn DslPartGet>,
+    parts: Vec<Box<dyn DslPartGet>>,
+}
+
+fn main() {
+    let assembly = dslAssembly(|o| {
+        o.main(Box::new(dslPart(|p| {
+            p.name("frame");
+        })))
+        .add_parts(Box::new(dslPartDefault()));
+    });
+
+    let _: &Option<Box<dyn DslPartGet>> = assembly.main_get();
+    let _: &[Box<dyn DslPartGet>] = assembly.parts_get();
+    assert_eq!(assembly.clone(), assembly);
+    assert!(format!("{assembly:?}").contains("frame"));
+}
@@ -0,0 +1,80 @@
+//! Compares the `Rc<RefCell<Node>>` tree with the arena-backed `ModelTree` on a
+//! model of 111 111 nodes, the size `ddd_cp::create_tree` simulates.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::{create_node, create_tree};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::cell::RefCell;
+
+const DEPTH: usize = 5;
+const BREADTH: usize = 10;
+const LAST_LEAF: &str = "Node 0.9.9.9.9.9";
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    group.sample_size(10);
+    group.bench_function("node", |b| b.iter(|| create_node(DEPTH, BREADTH)));
+    group.bench_function("model_tree", |b| b.iter(|| create_tree(DEPTH, BREADTH)));
+    group.finish();
+}
+
+fn traverse(c: &mut Criterion) {
+    let node = create_node(DEPTH, BREADTH);
+    let tree = create_tree(DEPTH, BREADTH);
+
+    let mut group = c.benchmark_group("traverse_down");
+    group.bench_function("node", |b| {
+        b.iter(|| {
+            let count = RefCell::new(0usize);
+            node.borrow().traverse_down(&|node| *count.borrow_mut() += node.item().name_get().len(), &|_| false);
+            black_box(count.into_inner())
+        })
+    });
+    group.bench_function("model_tree", |b| {
+        b.iter(|| {
+            let mut count = 0usize;
+            tree.traverse_down(tree.root(), |_, item| count += item.name_get().len(), |_, _| false);
+            black_box(count)
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("find_child");
+    group.bench_function("node", |b| {
+        b.iter(|| node.borrow().find_child(&|node| node.item().name_get() == LAST_LEAF).unwrap())
+    });
+    group.bench_function("model_tree", |b| {
+        b.iter(|| tree.find_child(tree.root(), |_, item| item.name_get() == LAST_LEAF).unwrap())
+    });
+    let leaf_id = tree.find_child(tree.root(), |_, item| item.name_get() == LAST_LEAF).unwrap();
+    let qualified_name = tree.qualified_name(leaf_id).to_owned();
+    group.bench_function("symbol_index", |b| b.iter(|| tree.symbols().get(black_box(&qualified_name)).unwrap()));
+    group.finish();
+
+    let leaf = node.borrow().find_child(&|node| node.item().name_get() == LAST_LEAF).unwrap();
+    let leaf_id = tree.find_child(tree.root(), |_, item| item.name_get() == LAST_LEAF).unwrap();
+    let mut group = c.benchmark_group("find_parent");
+    group.bench_function("node", |b| {
+        b.iter(|| leaf.borrow().find_parent(|item| item.name_get() == "Node 0").unwrap())
+    });
+    group.bench_function("model_tree", |b| {
+        b.iter(|| tree.find_parent(leaf_id, |item| item.name_get() == "Node 0").unwrap())
+    });
+    group.finish();
+}
+
+fn serialize(c: &mut Criterion) {
+    let node = create_node(DEPTH, BREADTH);
+    let tree = create_tree(DEPTH, BREADTH);
+
+    let mut group = c.benchmark_group("serialize_to_yaml");
+    group.sample_size(10);
+    group.bench_function("node", |b| b.iter(|| node.borrow().serialize_to_yaml().unwrap()));
+    group.bench_function("model_tree", |b| b.iter(|| tree.serialize_to_yaml().unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, build, traverse, serialize);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
+use ddd_runtime::AsDslItem;
 
-use ddd_derives::AsDslItem;
-
+#[allow(dead_code)]
 #[derive(AsDslItem)]
 struct Item {
     name: String,
@@ -9,17 +9,31 @@ struct Item {
     internal: bool,
     derived_as_type: String,
     initialized: bool,
+    /// Names used instead of the ones derived from `name`, see `naming::DslItemNames`.
+    naming: DslNamingImpl,
     //parent: Box<dyn DslItemGet>,
-    //derived_from: Box<dyn DslItem>,
-    //derived_items: Vec<Box<dyn DslItem>>,
+    derived_items: Vec<Box<dyn DslItemGet>>,
 }
 
-/*
+#[allow(dead_code)]
 #[derive(AsDslItem)]
+#[dsl(extends = Item)]
 struct Attribute {
-    //item: DslItem,
     nullable: bool,
     multi: bool,
     name_non_fluent: String,
 }
- */
\ No newline at end of file
+
+/// Overrides of the names derived from the name of an item, for the cases the
+/// inflection rules get wrong. Unset fields keep the derived name.
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Naming {
+    snake_case: String,
+    camel_case: String,
+    pascal_case: String,
+    kebab_case: String,
+    screaming_case: String,
+    plural: String,
+    singular: String,
+}
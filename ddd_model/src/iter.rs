@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::node::Node;
+
+type Children = Vec<Rc<RefCell<Node>>>;
+
+/// Depth first, pre-order iterator over a node tree, see `Node::dfs` and
+/// `Node::descendants`. Yields each node with its depth below the node the
+/// iteration started from.
+///
+/// A node is borrowed to read its children before it is yielded, not after,
+/// and the node the iteration starts from is read through `&self`: the
+/// iterator can be taken from a `borrow_mut()` guard and the yielded nodes can
+/// be borrowed mutably while iterating.
+///
+/// Children are only visited when the iterator moves past their parent, so
+/// `skip_subtree` right after a node was yielded prunes its descendants and
+/// `stop` ends the iteration.
+pub struct Dfs {
+    start: Option<(Rc<RefCell<Node>>, Children)>,
+    stack: Vec<(Rc<RefCell<Node>>, usize)>,
+    /// The children of the node yielded last, with their depth.
+    last: Option<(Children, usize)>,
+}
+
+impl Dfs {
+    /// Starts with `start` at depth 0 if given, else with `children` at depth 1.
+    pub(crate) fn new(start: Option<Rc<RefCell<Node>>>, children: Children) -> Self {
+        match start {
+            Some(start) => Dfs {
+                start: Some((start, children)),
+                stack: Vec::new(),
+                last: None,
+            },
+            None => Dfs {
+                start: None,
+                stack: Vec::new(),
+                last: Some((children, 1)),
+            },
+        }
+    }
+
+    /// The descendants of the node yielded last aren't visited.
+    pub fn skip_subtree(&mut self) {
+        self.last = None;
+    }
+
+    /// Nothing is yielded anymore.
+    pub fn stop(&mut self) {
+        self.start = None;
+        self.stack.clear();
+        self.last = None;
+    }
+}
+
+impl Iterator for Dfs {
+    type Item = (Rc<RefCell<Node>>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((children, depth)) = self.last.take() {
+            self.stack.extend(children.into_iter().rev().map(|child| (child, depth)));
+        }
+        if let Some((start, children)) = self.start.take() {
+            self.last = Some((children, 1));
+            return Some((start, 0));
+        }
+        let (node, depth) = self.stack.pop()?;
+        self.last = Some((node.borrow().children().to_vec(), depth + 1));
+        Some((node, depth))
+    }
+}
+
+/// Breadth first iterator over a node tree, see `Node::bfs`. Yields each node
+/// with its depth below the node the iteration started from, level by level.
+///
+/// Like `Dfs`, it reads the children of a node before yielding it,
+/// `skip_subtree` prunes the descendants of the node yielded last and `stop`
+/// ends the iteration.
+pub struct Bfs {
+    start: Option<(Rc<RefCell<Node>>, Children)>,
+    queue: VecDeque<(Rc<RefCell<Node>>, usize)>,
+    /// The children of the node yielded last, with their depth.
+    last: Option<(Children, usize)>,
+}
+
+impl Bfs {
+    pub(crate) fn new(start: Rc<RefCell<Node>>, children: Children) -> Self {
+        Bfs {
+            start: Some((start, children)),
+            queue: VecDeque::new(),
+            last: None,
+        }
+    }
+
+    /// The descendants of the node yielded last aren't visited.
+    pub fn skip_subtree(&mut self) {
+        self.last = None;
+    }
+
+    /// Nothing is yielded anymore.
+    pub fn stop(&mut self) {
+        self.start = None;
+        self.queue.clear();
+        self.last = None;
+    }
+}
+
+impl Iterator for Bfs {
+    type Item = (Rc<RefCell<Node>>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((children, depth)) = self.last.take() {
+            self.queue.extend(children.into_iter().map(|child| (child, depth)));
+        }
+        if let Some((start, children)) = self.start.take() {
+            self.last = Some((children, 1));
+            return Some((start, 0));
+        }
+        let (node, depth) = self.queue.pop_front()?;
+        self.last = Some((node.borrow().children().to_vec(), depth + 1));
+        Some((node, depth))
+    }
+}
@@ -1,5 +1,10 @@
-extern crate ddd_derives;
 extern crate serde;
 extern crate serde_yaml;
 pub mod node;
-pub mod item;
\ No newline at end of file
+pub mod item;
+pub mod iter;
+pub mod naming;
+pub mod reference;
+pub mod symbols;
+pub mod tree;
+pub mod visit;
\ No newline at end of file
@@ -0,0 +1,292 @@
+use std::ops::Range;
+
+use crate::item::{DslItemGet, DslNamingGet};
+
+/// The names generators use for an item, derived from its `name` unless the
+/// item overrides them through its `naming`.
+///
+/// Plural and singular keep the spelling of the name, `OrderItem` becomes
+/// `OrderItems`, the case variants take the name as it is.
+pub trait DslItemNames {
+    fn snake_case_name(&self) -> String;
+    fn camel_case_name(&self) -> String;
+    fn pascal_case_name(&self) -> String;
+    fn kebab_case_name(&self) -> String;
+    fn screaming_case_name(&self) -> String;
+    fn plural_name(&self) -> String;
+    fn singular_name(&self) -> String;
+}
+
+impl<T: DslItemGet + ?Sized> DslItemNames for T {
+    fn snake_case_name(&self) -> String {
+        let naming = self.naming_get();
+        if naming.is_snake_case_set() {
+            naming.snake_case_get().to_owned()
+        } else {
+            snake_case(self.name_get())
+        }
+    }
+
+    fn camel_case_name(&self) -> String {
+        let naming = self.naming_get();
+        if naming.is_camel_case_set() {
+            naming.camel_case_get().to_owned()
+        } else {
+            camel_case(self.name_get())
+        }
+    }
+
+    fn pascal_case_name(&self) -> String {
+        let naming = self.naming_get();
+        if naming.is_pascal_case_set() {
+            naming.pascal_case_get().to_owned()
+        } else {
+            pascal_case(self.name_get())
+        }
+    }
+
+    fn kebab_case_name(&self) -> String {
+        let naming = self.naming_get();
+        if naming.is_kebab_case_set() {
+            naming.kebab_case_get().to_owned()
+        } else {
+            kebab_case(self.name_get())
+        }
+    }
+
+    fn screaming_case_name(&self) -> String {
+        let naming = self.naming_get();
+        if naming.is_screaming_case_set() {
+            naming.screaming_case_get().to_owned()
+        } else {
+            screaming_case(self.name_get())
+        }
+    }
+
+    fn plural_name(&self) -> String {
+        let naming = self.naming_get();
+        if naming.is_plural_set() {
+            naming.plural_get().to_owned()
+        } else {
+            pluralize(self.name_get())
+        }
+    }
+
+    fn singular_name(&self) -> String {
+        let naming = self.naming_get();
+        if naming.is_singular_set() {
+            naming.singular_get().to_owned()
+        } else {
+            singularize(self.name_get())
+        }
+    }
+}
+
+/// `order_item` for `OrderItem`, `orderItem`, `order-item` or `ORDER_ITEM`.
+pub fn snake_case(name: &str) -> String {
+    join_words(name, "_", str::to_lowercase)
+}
+
+pub fn camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    for (i, word) in words(name).enumerate() {
+        if i == 0 {
+            camel.push_str(&word.to_lowercase());
+        } else {
+            camel.push_str(&capitalize(word));
+        }
+    }
+    camel
+}
+
+pub fn pascal_case(name: &str) -> String {
+    join_words(name, "", capitalize)
+}
+
+pub fn kebab_case(name: &str) -> String {
+    join_words(name, "-", str::to_lowercase)
+}
+
+pub fn screaming_case(name: &str) -> String {
+    join_words(name, "_", str::to_uppercase)
+}
+
+/// The plural of the last word of `name`, `OrderCategory` becomes `OrderCategories`.
+pub fn pluralize(name: &str) -> String {
+    inflect_last_word(name, |word| {
+        if let Some((_, plural)) = IRREGULAR.iter().find(|(singular, _)| *singular == word) {
+            return plural.to_string();
+        }
+        if UNCOUNTABLE.contains(&word) {
+            return word.to_owned();
+        }
+        if let Some((_, plural)) = F_TO_VES.iter().find(|(singular, _)| *singular == word) {
+            return plural.to_string();
+        }
+        if let Some(stem) = word.strip_suffix('y') {
+            if !stem.ends_with(['a', 'e', 'i', 'o', 'u']) {
+                return format!("{stem}ies");
+            }
+        }
+        if is_short_z_word(word) {
+            return format!("{word}zes");
+        }
+        if ["s", "x", "z", "ch", "sh"].iter().any(|suffix| word.ends_with(suffix)) {
+            return format!("{word}es");
+        }
+        format!("{word}s")
+    })
+}
+
+/// The singular of the last word of `name`, `OrderCategories` becomes `OrderCategory`.
+pub fn singularize(name: &str) -> String {
+    inflect_last_word(name, |word| {
+        if let Some((singular, _)) = IRREGULAR.iter().find(|(_, plural)| *plural == word) {
+            return singular.to_string();
+        }
+        if UNCOUNTABLE.contains(&word) {
+            return word.to_owned();
+        }
+        if let Some((singular, _)) = F_TO_VES.iter().find(|(_, plural)| *plural == word) {
+            return singular.to_string();
+        }
+        if let Some(stem) = word.strip_suffix("ies") {
+            if IE_WORDS.contains(&&word[..word.len() - 1]) {
+                return word[..word.len() - 1].to_owned();
+            }
+            return format!("{stem}y");
+        }
+        if let Some(stem) = word.strip_suffix("zes").filter(|stem| is_short_z_word(stem)) {
+            return stem.to_owned();
+        }
+        if let Some(stem) = word.strip_suffix("uses") {
+            // `statuses` and `buses`, but `houses` and `excuses` keep their `e`.
+            let singular = &word[..word.len() - 1];
+            if stem.ends_with(['a', 'e', 'o']) || USE_WORDS.contains(&singular) {
+                return singular.to_owned();
+            }
+            return word[..word.len() - 2].to_owned();
+        }
+        for suffix in ["sses", "xes", "zes", "ches", "shes"] {
+            if word.ends_with(suffix) {
+                return word[..word.len() - 2].to_owned();
+            }
+        }
+        if word.ends_with("ss") || word.ends_with("us") || word.ends_with("is") {
+            return word.to_owned();
+        }
+        word.strip_suffix('s').unwrap_or(word).to_owned()
+    })
+}
+
+const IRREGULAR: &[(&str, &str)] = &[
+    ("person", "people"),
+    ("child", "children"),
+    ("man", "men"),
+    ("woman", "women"),
+    ("mouse", "mice"),
+    ("goose", "geese"),
+    ("foot", "feet"),
+    ("tooth", "teeth"),
+    ("criterion", "criteria"),
+    ("datum", "data"),
+    ("index", "indices"),
+    ("matrix", "matrices"),
+    ("vertex", "vertices"),
+    ("analysis", "analyses"),
+    ("axis", "axes"),
+];
+
+const UNCOUNTABLE: &[&str] = &["equipment", "information", "metadata", "money", "news", "series", "species"];
+
+/// Words ending in `ie`, whose plural isn't made from a `y`.
+const IE_WORDS: &[&str] = &[
+    "auntie", "brownie", "calorie", "cookie", "die", "genie", "goalie", "hippie", "lie", "movie", "newbie", "pie",
+    "prairie", "rookie", "selfie", "smoothie", "sortie", "tie", "zombie",
+];
+
+/// Words ending in a consonant and `use`, whose plural isn't made from `us`.
+const USE_WORDS: &[&str] = &["abuse", "accuse", "excuse", "fuse", "misuse", "muse", "recluse", "refuse", "ruse", "use"];
+
+/// Short words ending in a single vowel and `z` double it in the plural, `quizzes`.
+fn is_short_z_word(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    (2..=4).contains(&bytes.len()) && bytes[bytes.len() - 1] == b'z' && b"aeiou".contains(&bytes[bytes.len() - 2])
+}
+
+const F_TO_VES: &[(&str, &str)] = &[
+    ("half", "halves"),
+    ("knife", "knives"),
+    ("leaf", "leaves"),
+    ("life", "lives"),
+    ("shelf", "shelves"),
+    ("wife", "wives"),
+    ("wolf", "wolves"),
+];
+
+/// Replaces the last word of `name` with `inflect` of its lowercase form, in the case of the word.
+fn inflect_last_word(name: &str, inflect: impl Fn(&str) -> String) -> String {
+    let Some(last) = word_ranges(name).pop() else {
+        return name.to_owned();
+    };
+    let word = &name[last.clone()];
+    let inflected = inflect(&word.to_lowercase());
+    let inflected = if word.len() > 1 && word.chars().all(|c| !c.is_lowercase()) {
+        inflected.to_uppercase()
+    } else if word.starts_with(char::is_uppercase) {
+        capitalize(&inflected)
+    } else {
+        inflected
+    };
+    format!("{}{inflected}{}", &name[..last.start], &name[last.end..])
+}
+
+fn join_words(name: &str, separator: &str, case: impl Fn(&str) -> String) -> String {
+    words(name).map(case).collect::<Vec<_>>().join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    word_ranges(name).into_iter().map(move |range| &name[range])
+}
+
+/// The words of `name`, split at separators and case changes. An acronym stays
+/// one word, `HTTPServer` is `HTTP` and `Server`, digits stay with the word before.
+fn word_ranges(name: &str) -> Vec<Range<usize>> {
+    let chars = name.char_indices().collect::<Vec<_>>();
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (i, &(index, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(start) = start.take() {
+                ranges.push(start..index);
+            }
+            continue;
+        }
+        let Some(word_start) = start else {
+            start = Some(index);
+            continue;
+        };
+        let prev = chars[i - 1].1;
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        let boundary = c.is_uppercase()
+            && (prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next.is_some_and(char::is_lowercase)));
+        if boundary {
+            ranges.push(word_start..index);
+            start = Some(index);
+        }
+    }
+    if let Some(start) = start {
+        ranges.push(start..name.len());
+    }
+    ranges
+}
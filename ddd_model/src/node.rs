@@ -1,29 +1,43 @@
 use std::rc::{Rc, Weak};
+use std::sync::Arc;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
 
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{BufReader, BufWriter, Read};
 use serde::{Serialize, Deserialize};
 
-use crate::item::{DslItem, DslItemGet};
+use ddd_runtime::source::indent;
+use ddd_runtime::validate::Diagnostic;
+
+use crate::item::DslItemGet;
+use crate::iter::{Bfs, Dfs};
+use crate::reference::{AnyRef, Ref, RefError, Resolved};
+use crate::tree::{ModelTree, NodeId};
 
 #[derive(Serialize, Deserialize)]
 pub struct Node {
     item: Box<dyn DslItemGet>,
-    //#[serde(skip_serializing)]
+    // Back-links aren't written, `from_reader` restores them after parsing.
+    #[serde(skip)]
     me: Option<Weak<RefCell<Node>>>,
-    //#[serde(skip_serializing)]
+    #[serde(skip)]
     parent: Option<Weak<RefCell<Node>>>,
     children: Vec<Rc<RefCell<Node>>>,
+    /// Paths `resolve` substitutes for a leading alias, in this subtree.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    aliases: BTreeMap<String, String>,
 }
 
 impl Node {
-    fn new(item: Box<dyn DslItemGet>) -> Rc<RefCell<Self>> {
+    pub fn new(item: Box<dyn DslItemGet>) -> Rc<RefCell<Self>> {
         let node = Rc::new(RefCell::new(Node {
             item,
             me: None,
             parent: None,
             children: Vec::new(),
+            aliases: BTreeMap::new(),
         }));
 
         node.borrow_mut().me = Some(Rc::downgrade(&node));
@@ -31,14 +45,92 @@ impl Node {
         node
     }
 
-    fn add_child(&mut self, item: Box<dyn DslItemGet>) -> Rc<RefCell<Node>> {
+    pub fn item(&self) -> &(dyn DslItemGet + 'static) {
+        self.item.as_ref()
+    }
+
+    pub fn item_mut(&mut self) -> &mut (dyn DslItemGet + 'static) {
+        self.item.as_mut()
+    }
+
+    pub fn children(&self) -> &[Rc<RefCell<Node>>] {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, item: Box<dyn DslItemGet>) -> Rc<RefCell<Node>> {
         let child = Node::new(item);
         child.borrow_mut().parent = self.me.clone();
         self.children.push(Rc::clone(&child));
         child
     }
 
-    fn traverse_up<F, P>(&self, on_node: F, stop_predicate: P)
+    pub fn parent(&self) -> Option<Rc<RefCell<Node>>> {
+        self.parent.as_ref().and_then(Weak::upgrade)
+    }
+
+    /// Like `add_child`, inserting the new child before the child at `index`.
+    pub fn insert_child_at(&mut self, index: usize, item: Box<dyn DslItemGet>) -> Result<Rc<RefCell<Node>>, EditError> {
+        if index > self.children.len() {
+            return Err(EditError::IndexOutOfBounds { index, len: self.children.len() });
+        }
+        let child = Node::new(item);
+        child.borrow_mut().parent = self.me.clone();
+        self.children.insert(index, Rc::clone(&child));
+        Ok(child)
+    }
+
+    /// Removes the child at `index` with its subtree, which becomes a tree of its own.
+    pub fn remove_child(&mut self, index: usize) -> Result<Rc<RefCell<Node>>, EditError> {
+        if index >= self.children.len() {
+            return Err(EditError::IndexOutOfBounds { index, len: self.children.len() });
+        }
+        let child = self.children.remove(index);
+        child.borrow_mut().parent = None;
+        Ok(child)
+    }
+
+    /// Removes `node` from the children of its parent, nothing happens for a root.
+    pub fn detach(node: &Rc<RefCell<Node>>) {
+        let Some(parent) = node.borrow().parent() else {
+            return;
+        };
+        parent.borrow_mut().children.retain(|child| !Rc::ptr_eq(child, node));
+        node.borrow_mut().parent = None;
+    }
+
+    /// Moves `node` with its subtree to the end of the children of `new_parent`.
+    /// Fails if `new_parent` is `node` or one of its descendants.
+    pub fn reparent(node: &Rc<RefCell<Node>>, new_parent: &Rc<RefCell<Node>>) -> Result<(), EditError> {
+        let mut current = Some(Rc::clone(new_parent));
+        while let Some(ancestor) = current {
+            if Rc::ptr_eq(&ancestor, node) {
+                return Err(EditError::Cycle);
+            }
+            current = ancestor.borrow().parent();
+        }
+
+        Node::detach(node);
+        node.borrow_mut().parent = Some(Rc::downgrade(new_parent));
+        new_parent.borrow_mut().children.push(Rc::clone(node));
+        Ok(())
+    }
+
+    /// Puts `item` in place of the item of this node and returns the previous one.
+    pub fn replace_item(&mut self, item: Box<dyn DslItemGet>) -> Box<dyn DslItemGet> {
+        std::mem::replace(&mut self.item, item)
+    }
+
+    /// Swaps the children at `a` and `b`.
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), EditError> {
+        let len = self.children.len();
+        if let Some(index) = [a, b].into_iter().find(|&index| index >= len) {
+            return Err(EditError::IndexOutOfBounds { index, len });
+        }
+        self.children.swap(a, b);
+        Ok(())
+    }
+
+    pub fn traverse_up<F, P>(&self, on_node: F, stop_predicate: P)
     where
         F: Fn(&Node),
         P: Fn(&Node) -> bool,
@@ -56,7 +148,7 @@ impl Node {
         }
     }
 
-    fn traverse_down<F, P>(&self, on_node: &F, stop_predicate: &P)
+    pub fn traverse_down<F, P>(&self, on_node: &F, stop_predicate: &P)
     where
         F: Fn(&Node),
         P: Fn(&Node) -> bool,
@@ -73,7 +165,44 @@ impl Node {
         }
     }
 
-    fn find_parent(&self, condition: impl Fn(&dyn DslItemGet) -> bool) -> Option<Weak<RefCell<Node>>> {
+    /// This node and its descendants, depth first. The depth of this node is 0.
+    pub fn dfs(&self) -> Dfs {
+        Dfs::new(Some(self.handle()), self.children.clone())
+    }
+
+    /// This node and its descendants, level by level. The depth of this node is 0.
+    pub fn bfs(&self) -> Bfs {
+        Bfs::new(self.handle(), self.children.clone())
+    }
+
+    /// The descendants of this node, depth first, starting at depth 1.
+    pub fn descendants(&self) -> Dfs {
+        Dfs::new(None, self.children.clone())
+    }
+
+    /// The parent of this node, its parent and so on up to the root, with
+    /// their distance to this node.
+    pub fn ancestors(&self) -> impl Iterator<Item = (Rc<RefCell<Node>>, usize)> {
+        std::iter::successors(self.parent().map(|parent| (parent, 1)), |(node, depth)| {
+            node.borrow().parent().map(|parent| (parent, depth + 1))
+        })
+    }
+
+    /// The other children of the parent of this node, in order. They are at the
+    /// depth of this node, so it is 0; a root has no siblings.
+    pub fn siblings(&self) -> impl Iterator<Item = (Rc<RefCell<Node>>, usize)> {
+        let siblings = match self.parent() {
+            Some(parent) => {
+                let me = self.handle();
+                let parent = parent.borrow();
+                parent.children.iter().filter(|child| !Rc::ptr_eq(child, &me)).cloned().collect()
+            }
+            None => Vec::new(),
+        };
+        siblings.into_iter().map(|sibling| (sibling, 0))
+    }
+
+    pub fn find_parent(&self, condition: impl Fn(&dyn DslItemGet) -> bool) -> Option<Weak<RefCell<Node>>> {
         let mut current = self.parent.clone();
         while let Some(node) = current.clone() {
             let node = node.upgrade().unwrap();
@@ -85,13 +214,13 @@ impl Node {
         None
     }
 
-    fn find_child<F>(&self, predicate: &F) -> Option<Rc<RefCell<Node>>>
+    pub fn find_child<F>(&self, predicate: &F) -> Option<Rc<RefCell<Node>>>
     where
         F: Fn(&Node) -> bool,
     {
         for child_rc in &self.children {
             let child = child_rc.borrow();
-            if predicate(&*child) {
+            if predicate(&child) {
                 return Some(Rc::clone(child_rc));
             }
 
@@ -103,23 +232,298 @@ impl Node {
         None
     }
 
-    fn filter_and_collect(&self, predicate: impl Fn(&dyn DslItemGet) -> bool) -> Vec<Rc<RefCell<Node>>> {
+    pub fn filter_and_collect(&self, predicate: impl Fn(&dyn DslItemGet) -> bool) -> Vec<Rc<RefCell<Node>>> {
         self.children.iter()
             .filter(|node| predicate(node.borrow().item.as_ref()))
             .cloned()
             .collect()
     }
 
-    fn serialize_to_yaml(&self) -> Result<String, serde_yaml::Error> {
+    /// The names of the items from the root down to this node, `shop::order::Order::id`.
+    ///
+    /// It is the path `resolve` takes, so the `namespace` of the items isn't
+    /// part of it: paths follow the tree, the namespace only scopes the
+    /// definitions checked by `check_duplicates`.
+    pub fn qualified_name(&self) -> String {
+        let mut names = self.ancestors().map(|(node, _)| node.borrow().item.name_get().to_owned()).collect::<Vec<_>>();
+        names.reverse();
+        names.push(self.item.name_get().to_owned());
+        names.join("::")
+    }
+
+    /// Lets paths resolved from this node or its descendants start with `alias`
+    /// instead of `path`, which is itself resolved from this node.
+    pub fn add_alias(&mut self, alias: &str, path: &str) {
+        self.aliases.insert(alias.to_owned(), path.to_owned());
+    }
+
+    /// The node at `path`, a `::` separated list of item names.
+    ///
+    /// Absolute paths start with the name of the root, `shop::order::Order::id`.
+    /// Relative paths start from this node, `self::id`, or its parent,
+    /// `..::Money`, and can go up again with `..`. A path can also start with an
+    /// alias added by `add_alias` to this node or one of its ancestors, the
+    /// nearest one wins.
+    ///
+    /// The other nodes along the path are borrowed, this one is read through
+    /// `&self`, so it can be called on a `borrow_mut()` guard of this node.
+    pub fn resolve(&self, path: &str) -> Option<Rc<RefCell<Node>>> {
+        let segments = path.split("::").collect::<Vec<_>>();
+        let (first, rest) = segments.split_first()?;
+        if !matches!(*first, "self" | "..") {
+            let scopes = std::iter::once(self.handle()).chain(self.ancestors().map(|(node, _)| node));
+            for scope in scopes {
+                let target = self.with_node(&scope, |scope| scope.aliases.get(*first).cloned());
+                if let Some(target) = target {
+                    let base = self.resolve_unaliased(scope, &target.split("::").collect::<Vec<_>>())?;
+                    return self.walk(base, rest);
+                }
+            }
+        }
+        self.resolve_unaliased(self.handle(), &segments)
+    }
+
+    fn resolve_unaliased(&self, from: Rc<RefCell<Node>>, segments: &[&str]) -> Option<Rc<RefCell<Node>>> {
+        let (first, rest) = segments.split_first()?;
+        if matches!(*first, "self" | "..") {
+            return self.walk(from, segments);
+        }
+        let root = self.root();
+        if !self.with_node(&root, |root| root.item.name_get() == *first) {
+            return None;
+        }
+        self.walk(root, rest)
+    }
+
+    fn walk(&self, mut node: Rc<RefCell<Node>>, segments: &[&str]) -> Option<Rc<RefCell<Node>>> {
+        for segment in segments {
+            let next = match *segment {
+                "self" => continue,
+                ".." => self.with_node(&node, Node::parent)?,
+                name => self.with_node(&node, |node| {
+                    node.children
+                        .iter()
+                        .find(|child| self.with_node(child, |child| child.item.name_get() == name))
+                        .cloned()
+                })?,
+            };
+            node = next;
+        }
+        Some(node)
+    }
+
+    /// Runs `f` on `node`, through `&self` when it is this node so that it isn't
+    /// borrowed again.
+    fn with_node<R>(&self, node: &Rc<RefCell<Node>>, f: impl FnOnce(&Node) -> R) -> R {
+        if self.me.as_ref().is_some_and(|me| me.as_ptr() == Rc::as_ptr(node)) {
+            f(self)
+        } else {
+            f(&node.borrow())
+        }
+    }
+
+    /// Looks up the item `reference` points to in the tree of this node, its
+    /// path resolved from this node like `resolve`.
+    ///
+    /// The other nodes along the path are borrowed, not this one, see `resolve`.
+    pub fn resolve_ref<T: 'static>(&self, reference: &Ref<T>) -> Result<Resolved<T>, RefError> {
+        let node = self.lookup(reference)?;
+        Ok(Resolved::new(node))
+    }
+
+    /// Resolves every reference held by the items of this subtree, a diagnostic
+    /// per dangling reference or reference to an item of another kind.
+    pub fn check_references(&self) -> Vec<Diagnostic> {
+        let diagnostics = RefCell::new(Vec::new());
+        self.traverse_down(
+            &|node| {
+                let item = node.item();
+                for (field, reference) in item.references() {
+                    if let Err(err) = node.lookup(reference) {
+                        let message = format!("{}: {err}", item.name_get());
+                        diagnostics.borrow_mut().push(Diagnostic::new(item.descriptor().name, field, message));
+                    }
+                }
+            },
+            &|_| false,
+        );
+        diagnostics.into_inner()
+    }
+
+    /// A diagnostic per item of the tree defining a name already defined in its
+    /// namespace, see `crate::symbols::SymbolIndex::duplicates`. The tree is
+    /// indexed by a `snapshot` taken for the check.
+    pub fn check_duplicates(&self) -> Vec<Diagnostic> {
+        self.snapshot().0.symbols().duplicates()
+    }
+
+    fn lookup(&self, reference: &dyn AnyRef) -> Result<Rc<RefCell<Node>>, RefError> {
+        let name = reference.name();
+        let dangling = || RefError::Dangling { name: name.to_owned() };
+        let node = self.resolve(name).ok_or_else(dangling)?;
+
+        let found = self.with_node(&node, |node| {
+            (!reference.accepts(node.item.as_any())).then(|| node.item.descriptor().name)
+        });
+        match found {
+            Some(found) => Err(RefError::WrongKind {
+                name: name.to_owned(),
+                expected: reference.target_type_name(),
+                found,
+            }),
+            None => Ok(node),
+        }
+    }
+
+    fn handle(&self) -> Rc<RefCell<Node>> {
+        self.me.as_ref().and_then(Weak::upgrade).expect("nodes are linked by Node::new and Node::from_reader")
+    }
+
+    fn root(&self) -> Rc<RefCell<Node>> {
+        let mut root = match self.parent.as_ref().and_then(Weak::upgrade) {
+            Some(parent) => parent,
+            None => return self.handle(),
+        };
+        loop {
+            let parent = root.borrow().parent.as_ref().and_then(Weak::upgrade);
+            match parent {
+                Some(parent) => root = parent,
+                None => return root,
+            }
+        }
+    }
+
+    /// Rust code building this tree again: a block that creates the nodes with
+    /// `Node::new` and `add_child` and evaluates to the root. The item builders
+    /// and `Node` have to be in scope where it is pasted.
+    pub fn to_dsl_source(&self) -> String {
+        let item = indent(&self.item.to_dsl_source());
+        let mut source = format!("{{\n    let root = Node::new(Box::new({}));\n", item.trim_start());
+        self.aliases_source("root", &mut source);
+        self.children_source("root", &mut 1, &mut source);
+        source.push_str("    root\n}");
+        source
+    }
+
+    fn children_source(&self, parent: &str, next: &mut usize, source: &mut String) {
+        for child in &self.children {
+            let child = child.borrow();
+            let add = format!("{parent}.borrow_mut().add_child(Box::new({}));", child.item.to_dsl_source());
+            if child.children.is_empty() && child.aliases.is_empty() {
+                source.push_str(&indent(&add));
+                source.push('\n');
+            } else {
+                let name = format!("node_{next}");
+                *next += 1;
+                source.push_str(&indent(&format!("let {name} = {add}")));
+                source.push('\n');
+                child.aliases_source(&name, source);
+                child.children_source(&name, next, source);
+            }
+        }
+    }
+
+    fn aliases_source(&self, node: &str, source: &mut String) {
+        for (alias, path) in &self.aliases {
+            source.push_str(&format!("    {node}.borrow_mut().add_alias({alias:?}, {path:?});\n"));
+        }
+    }
+
+    pub fn serialize_to_yaml(&self) -> Result<String, serde_yaml::Error> {
         serde_yaml::to_string(&self)
     }
 
-    fn write_to_yaml_file(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// A frozen copy of the whole tree of this node that many threads can read
+    /// at once, `Node` itself can't leave the thread it was built on, with the
+    /// id of this node in the copy. The items are cloned and the aliases carried
+    /// over, so the copy resolves paths alike.
+    ///
+    /// Like `resolve`, this node is read through `&self`, only the others are
+    /// borrowed.
+    pub fn snapshot(&self) -> (Arc<ModelTree>, NodeId) {
+        self.with_node(&self.root(), |root| {
+            let mut tree = ModelTree::new(root.item.clone());
+            let mut this = tree.root();
+            self.copy_into(root, &mut tree, this, &mut this);
+            (Arc::new(tree), this)
+        })
+    }
+
+    fn copy_into(&self, node: &Node, tree: &mut ModelTree, id: NodeId, this: &mut NodeId) {
+        for (alias, path) in &node.aliases {
+            tree.add_alias(id, alias, path);
+        }
+        for child in &node.children {
+            self.with_node(child, |child| {
+                let child_id = tree.add_child(id, child.item.clone());
+                if std::ptr::eq(child, self) {
+                    *this = child_id;
+                }
+                self.copy_into(child, tree, child_id, this);
+            });
+        }
+    }
+
+    /// Parses a tree written by `serialize_to_yaml` and links `me` and `parent`
+    /// of every node, like `Node::new` and `add_child` do.
+    pub fn load_yaml(yaml: &str) -> Result<Rc<RefCell<Node>>, serde_yaml::Error> {
+        let node: Node = serde_yaml::from_str(yaml)?;
+        Ok(Node::link(node))
+    }
+
+    /// Like `load_yaml`, reading the tree from `reader`.
+    pub fn from_reader(reader: impl Read) -> Result<Rc<RefCell<Node>>, serde_yaml::Error> {
+        let node: Node = serde_yaml::from_reader(reader)?;
+        Ok(Node::link(node))
+    }
+
+    pub fn read_from_yaml_file(file_path: &str) -> Result<Rc<RefCell<Node>>, Box<dyn std::error::Error>> {
+        let file = File::open(file_path)?;
+        Ok(Node::from_reader(BufReader::new(file))?)
+    }
+
+    fn link(node: Node) -> Rc<RefCell<Node>> {
+        let node = Rc::new(RefCell::new(node));
+        Node::link_children(&node);
+        node
+    }
+
+    fn link_children(node: &Rc<RefCell<Node>>) {
+        let me = Rc::downgrade(node);
+        let mut node = node.borrow_mut();
+        node.me = Some(Weak::clone(&me));
+        for child in &node.children {
+            child.borrow_mut().parent = Some(Weak::clone(&me));
+            Node::link_children(child);
+        }
+    }
+
+    pub fn write_to_yaml_file(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let file = File::create(file_path)?;
         let writer = BufWriter::new(file);
         let mut serializer = serde_yaml::Serializer::new(writer);
         self.serialize(&mut serializer)?;
         Ok(())
     }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// The index is past the children of the node.
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The node would become a descendant of itself.
+    Cycle,
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::IndexOutOfBounds { index, len } => {
+                write!(f, "child index {index} is out of bounds, the node has {len} children")
+            }
+            EditError::Cycle => write!(f, "a node can't be moved below itself"),
+        }
+    }
+}
 
-}
\ No newline at end of file
+impl std::error::Error for EditError {}
@@ -0,0 +1,73 @@
+use std::cell::{self, RefCell};
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+pub use ddd_runtime::reference::{AnyRef, Ref};
+
+use crate::node::Node;
+
+/// The node a `Ref<T>` points to, checked to hold a `T`. See `Node::resolve_ref`.
+pub struct Resolved<T> {
+    node: Rc<RefCell<Node>>,
+    target: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> Resolved<T> {
+    pub(crate) fn new(node: Rc<RefCell<Node>>) -> Self {
+        Resolved {
+            node,
+            target: PhantomData,
+        }
+    }
+
+    pub fn node(&self) -> &Rc<RefCell<Node>> {
+        &self.node
+    }
+
+    pub fn borrow(&self) -> cell::Ref<'_, T> {
+        cell::Ref::map(self.node.borrow(), |node| {
+            node.item().as_any().downcast_ref().expect("checked by Node::resolve_ref")
+        })
+    }
+
+    pub fn borrow_mut(&self) -> cell::RefMut<'_, T> {
+        cell::RefMut::map(self.node.borrow_mut(), |node| {
+            node.item_mut().as_any_mut().downcast_mut().expect("checked by Node::resolve_ref")
+        })
+    }
+}
+
+impl<T> Clone for Resolved<T> {
+    fn clone(&self) -> Self {
+        Resolved {
+            node: Rc::clone(&self.node),
+            target: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefError {
+    /// No item of the model has the qualified name.
+    Dangling { name: String },
+    /// The item has another type than the one the reference points to.
+    WrongKind {
+        name: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl fmt::Display for RefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RefError::Dangling { name } => write!(f, "dangling reference, no item is named `{name}`"),
+            RefError::WrongKind { name, expected, found } => {
+                write!(f, "`{name}` is an item of kind {found}, expected a {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RefError {}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use ddd_runtime::validate::Diagnostic;
+
+use crate::tree::NodeId;
+
+/// The nodes of a `ModelTree` by qualified name and by item kind, kept up to
+/// date by the tree as nodes are added and items renamed. See `ModelTree::symbols`.
+///
+/// Definitions are tracked by the name an item defines: `namespace::name` for
+/// an item that sets its `namespace`, its qualified name for the others.
+#[derive(Default)]
+pub struct SymbolIndex {
+    by_name: HashMap<String, Vec<(NodeId, &'static str)>>,
+    by_definition: HashMap<String, Vec<(NodeId, &'static str)>>,
+    by_kind: HashMap<&'static str, Vec<NodeId>>,
+}
+
+impl SymbolIndex {
+    /// The node at `qualified_name`, the first one if several have that path.
+    pub fn get(&self, qualified_name: &str) -> Option<NodeId> {
+        self.by_name.get(qualified_name).and_then(|ids| ids.first()).map(|&(id, _)| id)
+    }
+
+    /// Every node defining `name`, `shop::Order` for an item `Order` of the
+    /// namespace `shop`, with the kind of its item, in the order they were added.
+    pub fn definitions(&self, name: &str) -> &[(NodeId, &'static str)] {
+        self.by_definition.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// The nodes whose item is of `kind`, the name of its descriptor: `"Attribute"`.
+    pub fn of_kind(&self, kind: &str) -> &[NodeId] {
+        self.by_kind.get(kind).map_or(&[], Vec::as_slice)
+    }
+
+    /// A diagnostic per definition of a name after the first one: two items of
+    /// the same name in the same namespace. It is reported on the kind of the
+    /// item defining the name again.
+    pub fn duplicates(&self) -> Vec<Diagnostic> {
+        let mut duplicates = self.by_definition.iter().filter(|(_, ids)| ids.len() > 1).collect::<Vec<_>>();
+        duplicates.sort_by_key(|(name, _)| *name);
+        duplicates
+            .into_iter()
+            .flat_map(|(name, ids)| {
+                ids[1..].iter().map(move |&(_, kind)| Diagnostic::new(kind, "name", format!("`{name}` is already defined")))
+            })
+            .collect()
+    }
+
+    pub(crate) fn insert(&mut self, qualified_name: &str, definition: &str, kind: &'static str, id: NodeId) {
+        self.by_name.entry(qualified_name.to_owned()).or_default().push((id, kind));
+        self.by_definition.entry(definition.to_owned()).or_default().push((id, kind));
+        self.by_kind.entry(kind).or_default().push(id);
+    }
+
+    pub(crate) fn rename(&mut self, from: &str, to: &str, id: NodeId, kind: &'static str) {
+        Self::move_node(&mut self.by_name, from, to, id, kind);
+    }
+
+    pub(crate) fn redefine(&mut self, from: &str, to: &str, id: NodeId, kind: &'static str) {
+        Self::move_node(&mut self.by_definition, from, to, id, kind);
+    }
+
+    fn move_node(
+        map: &mut HashMap<String, Vec<(NodeId, &'static str)>>,
+        from: &str,
+        to: &str,
+        id: NodeId,
+        kind: &'static str,
+    ) {
+        if let Some(ids) = map.get_mut(from) {
+            ids.retain(|&(other, _)| other != id);
+            if ids.is_empty() {
+                map.remove(from);
+            }
+        }
+        let ids = map.entry(to.to_owned()).or_default();
+        let at = ids.partition_point(|&(other, _)| other < id);
+        ids.insert(at, (id, kind));
+    }
+}
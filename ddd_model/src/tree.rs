@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::ops::{Deref, DerefMut};
+
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::item::DslItemGet;
+use crate::symbols::SymbolIndex;
+
+/// A node of a `ModelTree`. Ids stay valid for the lifetime of the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Position of the node in the tree, in the order the nodes were added.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+struct Entry {
+    item: Box<dyn DslItemGet>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    /// The qualified name the node is indexed under.
+    symbol: String,
+    /// The name the node is indexed under as a definition, see `SymbolIndex`.
+    definition: String,
+    aliases: BTreeMap<String, String>,
+}
+
+/// A model tree whose nodes live in one arena and refer to each other by `NodeId`.
+///
+/// It offers the traversal, search and serialization of `Node` without
+/// `Rc<RefCell<_>>`: accesses are checked by the borrow checker and the nodes
+/// are stored next to each other. Both serialize to the same YAML.
+///
+/// Unlike `Node`, a `ModelTree` is `Send + Sync`: an `Arc<ModelTree>`, see
+/// `Node::snapshot`, can be read by several generators running in parallel.
+///
+/// The nodes are indexed by qualified name and item kind as they are added,
+/// see `symbols`.
+pub struct ModelTree {
+    nodes: Vec<Entry>,
+    symbols: SymbolIndex,
+}
+
+impl ModelTree {
+    pub fn new(item: Box<dyn DslItemGet>) -> Self {
+        let mut tree = ModelTree {
+            nodes: Vec::new(),
+            symbols: SymbolIndex::default(),
+        };
+        tree.push(item, None);
+        tree
+    }
+
+    fn push(&mut self, item: Box<dyn DslItemGet>, parent: Option<NodeId>) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let symbol = self.symbol(parent, item.name_get());
+        let definition = Self::definition(&symbol, item.as_ref());
+        self.symbols.insert(&symbol, &definition, item.descriptor().name, id);
+        self.nodes.push(Entry {
+            item,
+            parent,
+            children: Vec::new(),
+            symbol,
+            definition,
+            aliases: BTreeMap::new(),
+        });
+        id
+    }
+
+    fn symbol(&self, parent: Option<NodeId>, name: &str) -> String {
+        match parent {
+            Some(parent) => format!("{}::{name}", self.nodes[parent.0].symbol),
+            None => name.to_owned(),
+        }
+    }
+
+    fn definition(symbol: &str, item: &dyn DslItemGet) -> String {
+        match item.namespace_get() {
+            "" => symbol.to_owned(),
+            namespace => format!("{namespace}::{}", item.name_get()),
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn item(&self, id: NodeId) -> &(dyn DslItemGet + 'static) {
+        self.nodes[id.0].item.as_ref()
+    }
+
+    /// The item of `id` for editing. Renaming it or changing its namespace
+    /// updates the symbol index when the returned guard is dropped.
+    pub fn item_mut(&mut self, id: NodeId) -> ItemMut<'_> {
+        ItemMut { tree: self, id }
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id.0].children
+    }
+
+    pub fn add_child(&mut self, parent: NodeId, item: Box<dyn DslItemGet>) -> NodeId {
+        let child = self.push(item, Some(parent));
+        self.nodes[parent.0].children.push(child);
+        child
+    }
+
+    /// The names of the items from the root down to `id`, `shop::order::Order::id`,
+    /// like `Node::qualified_name`.
+    pub fn qualified_name(&self, id: NodeId) -> &str {
+        &self.nodes[id.0].symbol
+    }
+
+    /// Like `Node::add_alias`, for paths resolved from `id` or its descendants.
+    pub fn add_alias(&mut self, id: NodeId, alias: &str, path: &str) {
+        self.nodes[id.0].aliases.insert(alias.to_owned(), path.to_owned());
+    }
+
+    /// The node at `path` resolved from `id`, like `Node::resolve`.
+    pub fn resolve(&self, id: NodeId, path: &str) -> Option<NodeId> {
+        let segments = path.split("::").collect::<Vec<_>>();
+        let (first, rest) = segments.split_first()?;
+        if !matches!(*first, "self" | "..") {
+            let mut scope = Some(id);
+            while let Some(id) = scope {
+                if let Some(target) = self.nodes[id.0].aliases.get(*first) {
+                    let base = self.resolve_unaliased(id, &target.split("::").collect::<Vec<_>>())?;
+                    return self.walk(base, rest);
+                }
+                scope = self.parent(id);
+            }
+        }
+        self.resolve_unaliased(id, &segments)
+    }
+
+    fn resolve_unaliased(&self, id: NodeId, segments: &[&str]) -> Option<NodeId> {
+        let (first, rest) = segments.split_first()?;
+        if matches!(*first, "self" | "..") {
+            return self.walk(id, segments);
+        }
+        if self.item(self.root()).name_get() != *first {
+            return None;
+        }
+        self.walk(self.root(), rest)
+    }
+
+    fn walk(&self, mut id: NodeId, segments: &[&str]) -> Option<NodeId> {
+        for segment in segments {
+            id = match *segment {
+                "self" => continue,
+                ".." => self.parent(id)?,
+                name => *self.children(id).iter().find(|&&child| self.item(child).name_get() == name)?,
+            };
+        }
+        Some(id)
+    }
+
+    /// The nodes by qualified name and item kind. Lookups take constant time,
+    /// unlike `find_child`.
+    pub fn symbols(&self) -> &SymbolIndex {
+        &self.symbols
+    }
+
+    /// Indexes `id` and its descendants again if the name or the namespace of
+    /// the item of `id` changed.
+    fn reindex(&mut self, id: NodeId) {
+        let entry = &self.nodes[id.0];
+        let symbol = self.symbol(entry.parent, entry.item.name_get());
+        if symbol == entry.symbol && Self::definition(&symbol, entry.item.as_ref()) == entry.definition {
+            return;
+        }
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let entry = &self.nodes[id.0];
+            let kind = entry.item.descriptor().name;
+            let symbol = self.symbol(entry.parent, entry.item.name_get());
+            let definition = Self::definition(&symbol, entry.item.as_ref());
+            self.symbols.rename(&entry.symbol, &symbol, id, kind);
+            self.symbols.redefine(&entry.definition, &definition, id, kind);
+            let entry = &mut self.nodes[id.0];
+            entry.symbol = symbol;
+            entry.definition = definition;
+            stack.extend(self.children(id));
+        }
+    }
+
+    pub fn traverse_up<F, P>(&self, id: NodeId, mut on_node: F, stop_predicate: P)
+    where
+        F: FnMut(NodeId, &dyn DslItemGet),
+        P: Fn(NodeId, &dyn DslItemGet) -> bool,
+    {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let item = self.item(id);
+            if stop_predicate(id, item) {
+                return;
+            }
+            on_node(id, item);
+            current = self.parent(id);
+        }
+    }
+
+    /// Visits `id` and its descendants depth first, in the order of `Node::traverse_down`.
+    /// The subtree of a node matching `stop_predicate` is skipped.
+    pub fn traverse_down<F, P>(&self, id: NodeId, mut on_node: F, stop_predicate: P)
+    where
+        F: FnMut(NodeId, &dyn DslItemGet),
+        P: Fn(NodeId, &dyn DslItemGet) -> bool,
+    {
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let item = self.item(id);
+            if stop_predicate(id, item) {
+                continue;
+            }
+            on_node(id, item);
+            stack.extend(self.children(id).iter().rev());
+        }
+    }
+
+    pub fn find_parent(&self, id: NodeId, condition: impl Fn(&dyn DslItemGet) -> bool) -> Option<NodeId> {
+        let mut current = self.parent(id);
+        while let Some(id) = current {
+            if condition(self.item(id)) {
+                return Some(id);
+            }
+            current = self.parent(id);
+        }
+        None
+    }
+
+    /// The first descendant of `id` matching `predicate`, depth first.
+    pub fn find_child(&self, id: NodeId, predicate: impl Fn(NodeId, &dyn DslItemGet) -> bool) -> Option<NodeId> {
+        let mut stack = self.children(id).iter().rev().copied().collect::<Vec<_>>();
+        while let Some(id) = stack.pop() {
+            if predicate(id, self.item(id)) {
+                return Some(id);
+            }
+            stack.extend(self.children(id).iter().rev());
+        }
+        None
+    }
+
+    /// The children of `id` whose item matches `predicate`.
+    pub fn filter_and_collect(&self, id: NodeId, predicate: impl Fn(&dyn DslItemGet) -> bool) -> Vec<NodeId> {
+        self.children(id)
+            .iter()
+            .copied()
+            .filter(|&child| predicate(self.item(child)))
+            .collect()
+    }
+
+    pub fn serialize_to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self)
+    }
+
+    pub fn write_to_yaml_file(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let file = File::create(file_path)?;
+        let writer = BufWriter::new(file);
+        let mut serializer = serde_yaml::Serializer::new(writer);
+        self.serialize(&mut serializer)?;
+        Ok(())
+    }
+
+    pub fn load_yaml(yaml: &str) -> Result<ModelTree, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    pub fn from_reader(reader: impl Read) -> Result<ModelTree, serde_yaml::Error> {
+        serde_yaml::from_reader(reader)
+    }
+
+    pub fn read_from_yaml_file(file_path: &str) -> Result<ModelTree, Box<dyn std::error::Error>> {
+        let file = File::open(file_path)?;
+        Ok(ModelTree::from_reader(BufReader::new(file))?)
+    }
+}
+
+/// The item of a node being edited, see `ModelTree::item_mut`.
+pub struct ItemMut<'a> {
+    tree: &'a mut ModelTree,
+    id: NodeId,
+}
+
+impl Deref for ItemMut<'_> {
+    type Target = dyn DslItemGet;
+
+    fn deref(&self) -> &Self::Target {
+        self.tree.nodes[self.id.0].item.as_ref()
+    }
+}
+
+impl DerefMut for ItemMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.tree.nodes[self.id.0].item.as_mut()
+    }
+}
+
+impl Drop for ItemMut<'_> {
+    fn drop(&mut self) {
+        self.tree.reindex(self.id);
+    }
+}
+
+/// A node and its subtree, in the shape `Node` is serialized in.
+struct SerializeNode<'a> {
+    tree: &'a ModelTree,
+    id: NodeId,
+}
+
+impl Serialize for SerializeNode<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let entry = &self.tree.nodes[self.id.0];
+        let mut node = serializer.serialize_struct("Node", 3)?;
+        node.serialize_field("item", &entry.item)?;
+        node.serialize_field("children", &SerializeChildren { tree: self.tree, id: self.id })?;
+        if entry.aliases.is_empty() {
+            node.skip_field("aliases")?;
+        } else {
+            node.serialize_field("aliases", &entry.aliases)?;
+        }
+        node.end()
+    }
+}
+
+struct SerializeChildren<'a> {
+    tree: &'a ModelTree,
+    id: NodeId,
+}
+
+impl Serialize for SerializeChildren<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let children = self.tree.children(self.id);
+        let mut seq = serializer.serialize_seq(Some(children.len()))?;
+        for &id in children {
+            seq.serialize_element(&SerializeNode { tree: self.tree, id })?;
+        }
+        seq.end()
+    }
+}
+
+impl Serialize for ModelTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializeNode { tree: self, id: self.root() }.serialize(serializer)
+    }
+}
+
+#[derive(Deserialize)]
+struct DeserializeNode {
+    item: Box<dyn DslItemGet>,
+    #[serde(default)]
+    children: Vec<DeserializeNode>,
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+}
+
+impl<'de> Deserialize<'de> for ModelTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let root = DeserializeNode::deserialize(deserializer)?;
+        let mut tree = ModelTree::new(root.item);
+        tree.nodes[0].aliases = root.aliases;
+        let mut pending = vec![(tree.root(), root.children)];
+        while let Some((parent, children)) = pending.pop() {
+            for child in children {
+                let id = tree.add_child(parent, child.item);
+                tree.nodes[id.0].aliases = child.aliases;
+                pending.push((id, child.children));
+            }
+        }
+        Ok(tree)
+    }
+}
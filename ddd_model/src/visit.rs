@@ -0,0 +1,3 @@
+//! Visitors over a `Node` tree of the items of this crate.
+
+ddd_runtime::dsl_visitors!(node = crate::node::Node; crate::item::Item, crate::item::Attribute);
@@ -0,0 +1,66 @@
+//! Models shared by the tests and the benchmarks.
+#![allow(dead_code)]
+
+use ddd_model::item::{dslAttribute, dslItem, DslItemGet, DslItemSet};
+use ddd_model::node::Node;
+use ddd_model::tree::{ModelTree, NodeId};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub type NodeRef = Rc<RefCell<Node>>;
+
+/// An `Item` named `name` in the namespace `shop`.
+pub fn item(name: &str) -> Box<dyn DslItemGet> {
+    let mut item = dslItem(|_| {});
+    item.name(name).namespace("shop");
+    Box::new(item)
+}
+
+/// An `Attribute` named `name`, without namespace.
+pub fn attribute(name: &str) -> Box<dyn DslItemGet> {
+    Box::new(dslAttribute(|o| {
+        o.name(name);
+    }))
+}
+
+pub fn name(node: &NodeRef) -> String {
+    node.borrow().item().name_get().to_owned()
+}
+
+/// A tree of `item`s `depth` levels below the root `Node 0`, each with
+/// `breadth` children named after their parent: `Node 0.1.0`.
+pub fn create_node(depth: usize, breadth: usize) -> NodeRef {
+    fn create_children(parent: &NodeRef, name: &str, depth: usize, breadth: usize) {
+        if depth == 0 {
+            return;
+        }
+        for i in 0..breadth {
+            let name = format!("{name}.{i}");
+            let child = parent.borrow_mut().add_child(item(&name));
+            create_children(&child, &name, depth - 1, breadth);
+        }
+    }
+
+    let root = Node::new(item("Node 0"));
+    create_children(&root, "Node 0", depth, breadth);
+    root
+}
+
+/// The tree of `create_node` as a `ModelTree`.
+pub fn create_tree(depth: usize, breadth: usize) -> ModelTree {
+    fn create_children(tree: &mut ModelTree, parent: NodeId, name: &str, depth: usize, breadth: usize) {
+        if depth == 0 {
+            return;
+        }
+        for i in 0..breadth {
+            let name = format!("{name}.{i}");
+            let child = tree.add_child(parent, item(&name));
+            create_children(tree, child, &name, depth - 1, breadth);
+        }
+    }
+
+    let mut tree = ModelTree::new(item("Node 0"));
+    let root = tree.root();
+    create_children(&mut tree, root, "Node 0", depth, breadth);
+    tree
+}
@@ -0,0 +1,75 @@
+use ddd_runtime::{AsDslEnum, AsDslItem};
+
+#[allow(dead_code)]
+#[derive(AsDslEnum)]
+enum Visibility {
+    Public,
+    #[dsl(value = "~")]
+    Package,
+    #[dsl(rename = "private", value = "-", doc = "Only visible inside the item.")]
+    Private,
+}
+
+#[test]
+fn dsl_enum() {
+    let visibility = dslVisibility(|o| *o = DslVisibilityImpl::Private);
+
+    assert_eq!(visibility.name_get(), "private");
+    assert_eq!(visibility.ordinal_get(), 2);
+    assert_eq!(visibility.value_get(), Some("-"));
+    assert_eq!(DslVisibilityImpl::Public.value_get(), None);
+    assert_eq!(dslVisibilityDefault(), DslVisibilityImpl::Public);
+    assert_eq!(DslVisibilityImpl::LITERALS.len(), 3);
+    assert_eq!(DslVisibilityImpl::from_ordinal(1), Some(DslVisibilityImpl::Package));
+    assert_eq!(DslVisibilityImpl::from_ordinal(3), None);
+}
+
+#[test]
+fn dsl_enum_parse_display() {
+    for literal in DslVisibilityImpl::LITERALS {
+        assert_eq!(literal.to_string().parse::<DslVisibilityImpl>(), Ok(*literal));
+    }
+    assert_eq!(DslVisibilityImpl::parse("Private"), None);
+    assert_eq!(
+        "Protected".parse::<DslVisibilityImpl>(),
+        Err("unknown Visibility literal `Protected`".to_string())
+    );
+}
+
+#[test]
+fn dsl_enum_serde() {
+    let yaml = serde_yaml::to_string(&DslVisibilityImpl::Private).unwrap();
+    assert_eq!(yaml.trim(), "private");
+    let literal: DslVisibilityImpl = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(literal, DslVisibilityImpl::Private);
+
+    let boxed: Box<dyn DslVisibilityGet> = Box::new(DslVisibilityImpl::Package);
+    let yaml = serde_yaml::to_string(&boxed).unwrap();
+    let boxed: Box<dyn DslVisibilityGet> = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(boxed.name_get(), "Package");
+}
+
+#[test]
+fn dsl_enum_in_item() {
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Operation {
+        name: String,
+        visibility: DslVisibilityImpl,
+    }
+
+    let operation = dslOperation(|o| {
+        o.name("create");
+    });
+    assert_eq!(*operation.visibility_get(), DslVisibilityImpl::Public);
+
+    let operation = dslOperation(|o| {
+        o.name("delete")
+            .visibility_with(|v| *v = DslVisibilityImpl::Package);
+    });
+    assert_eq!(*operation.visibility_get(), DslVisibilityImpl::Package);
+    assert_eq!(
+        operation.to_dsl_source(),
+        "dslOperation(|o| {\n    o.name(\"delete\");\n    o.visibility(DslVisibilityImpl::Package);\n})"
+    );
+}
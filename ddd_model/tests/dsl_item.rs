@@ -1,10 +1,9 @@
-extern crate ddd_derives;
-
-use ddd_derives::AsDslItem;
+use ddd_runtime::AsDslItem;
 
 #[test]
 fn dsl_item() {
 
+    #[allow(dead_code)]
     #[derive(AsDslItem)]
     struct Command {
         executable: String,
@@ -19,4 +18,395 @@ fn dsl_item() {
 
     assert_eq!(command.executable_get(), "cargo");
 
-}
\ No newline at end of file
+}
+#[test]
+fn dsl_item_vec() {
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Enum {
+        name: String,
+        literals: Vec<String>,
+        ordinals: Vec<u32>,
+    }
+
+    let mut e = dslEnum(|o| {
+        o.name("Color")
+            .add_literals("Red")
+            .extend_literals(&["Green", "Blue"])
+            .extend_ordinals(vec![1, 2]);
+    });
+
+    assert_eq!(e.literals_get(), ["Red", "Green", "Blue"]);
+    assert_eq!(e.ordinals_get(), &[1, 2]);
+
+    e.clear_literals().add_ordinals(3);
+    assert!(e.literals_get().is_empty());
+    assert_eq!(e.ordinals_get(), &[1, 2, 3]);
+}
+
+#[test]
+fn dsl_item_option() {
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Attribute {
+        name: String,
+        default_value: Option<String>,
+        length: Option<u32>,
+    }
+
+    let mut attribute = dslAttribute(|o| {
+        o.name("id").default_value("0").length(36);
+    });
+
+    assert_eq!(attribute.default_value_get(), Some("0"));
+    assert_eq!(attribute.length_get(), Some(&36));
+
+    attribute.unset_default_value();
+    assert_eq!(attribute.default_value_get(), None);
+    assert_eq!(dslAttributeDefault().length_get(), None);
+}
+
+#[test]
+fn dsl_item_attributes() {
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Entity {
+        #[dsl(required, doc = "Name of the entity.")]
+        name: String,
+        #[dsl(default = "shop")]
+        namespace: String,
+        #[dsl(default = "8")]
+        version: u32,
+        #[dsl(rename = "is_aggregate")]
+        aggregate: bool,
+        #[dsl(skip)]
+        cache: std::collections::HashMap<String, String>,
+    }
+
+    let entity = dslEntity(|o| {
+        o.name("Order").is_aggregate(true);
+    });
+
+    assert_eq!(entity.name_get(), "Order");
+    assert_eq!(entity.namespace_get(), "shop");
+    assert_eq!(entity.version_get(), &8);
+    assert!(entity.is_aggregate_get());
+    assert_eq!(DslEntityImpl::default().namespace_get(), "shop");
+
+    let yaml = serde_yaml::to_string(&entity).unwrap();
+    assert!(yaml.contains("is_aggregate: true"));
+}
+
+#[test]
+#[should_panic(expected = "dslEntity: required fields are not set: name")]
+fn dsl_item_required() {
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Entity {
+        #[dsl(required)]
+        name: String,
+        namespace: String,
+    }
+
+    dslEntity(|o| {
+        o.namespace("shop");
+    });
+}
+
+#[test]
+fn dsl_item_try_builder() {
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Entity {
+        #[dsl(required)]
+        name: String,
+        namespace: String,
+    }
+
+    let missing = try_dslEntity(|o| {
+        o.namespace("shop");
+    });
+    assert_eq!(missing.err(), Some(vec!["name"]));
+    let entity = try_dslEntity(|o| {
+        o.name("Order");
+    });
+    assert_eq!(entity.unwrap().name_get(), "Order");
+}
+
+#[test]
+fn dsl_item_extends() {
+    use ddd_model::item::{dslAttribute, DslAttributeGet, DslAttributeSet, DslItem, DslItemGet, DslItemSet};
+
+    fn item_name<T: DslItem>(item: &T) -> &str {
+        item.name_get()
+    }
+
+    let attribute = dslAttribute(|o| {
+        o.name("id").namespace("shop").nullable(true);
+    });
+
+    assert_eq!(item_name(&attribute), "id");
+    assert_eq!(attribute.namespace_get(), "shop");
+    assert!(attribute.nullable_get());
+
+    let boxed: Box<dyn DslAttributeGet> = Box::new(attribute.clone());
+    assert_eq!(boxed.name_get(), "id");
+
+    let item: Box<dyn DslItemGet> = Box::new(attribute);
+    let yaml = serde_yaml::to_string(&item).unwrap();
+    let loaded: Box<dyn DslItemGet> = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(loaded.name_get(), "id");
+    assert_eq!(loaded.namespace_get(), "shop");
+}
+
+#[test]
+fn dsl_item_closures() {
+    use ddd_model::item::{dslAttribute, DslAttributeGet, DslAttributeImpl, DslAttributeSet, DslItemGet, DslItemSet};
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Entity {
+        name: String,
+        id: Box<DslAttributeImpl>,
+        attributes: Vec<DslAttributeImpl>,
+    }
+
+    let columns = vec![("code", false), ("comment", true)];
+    let entity_name = String::from("Order");
+
+    let entity = dslEntity(|o| {
+        o.name(&entity_name).id_with(|a| {
+            a.name("id");
+        });
+        for (column, nullable) in &columns {
+            o.add_attributes_with(|a| {
+                a.name(column).nullable(*nullable);
+            });
+        }
+    });
+
+    assert_eq!(entity.name_get(), "Order");
+    assert_eq!(entity.id_get().as_ref().unwrap().name_get(), "id");
+    let attributes = entity.attributes_get();
+    assert_eq!(attributes.len(), 2);
+    assert_eq!(attributes[1].name_get(), "comment");
+    assert!(attributes[1].nullable_get());
+}
+
+#[test]
+fn dsl_item_generics() {
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Constant<'a, T>
+    where
+        T: Copy,
+    {
+        name: String,
+        value: T,
+        alternatives: Vec<T>,
+        #[dsl(skip)]
+        source: &'a str,
+    }
+
+    let limit = 255u8;
+    let constant: DslConstantImpl<u8> = dslConstant(|o| {
+        o.name("max").value(limit).add_alternatives(127);
+    });
+
+    assert_eq!(constant.name_get(), "max");
+    assert_eq!(constant.value_get(), &255);
+    assert_eq!(constant.alternatives_get(), &[127]);
+    assert_eq!(dslConstantDefault::<u16>().value_get(), &0);
+
+    let yaml = serde_yaml::to_string(&constant).unwrap();
+    let loaded: DslConstantImpl<u8> = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(loaded, constant);
+}
+
+#[test]
+fn dsl_item_primitives() {
+    use std::net::Ipv4Addr;
+    use std::path::Path;
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    #[dsl(type_map(ty = Ipv4Addr, default = Ipv4Addr::LOCALHOST, setter = [u8; 4], into = Ipv4Addr::from(value)))]
+    struct Primitives {
+        string: std::string::String,
+        static_str: &'static str,
+        #[dsl(default = "target")]
+        path: std::path::PathBuf,
+        boolean: bool,
+        character: char,
+        int8: i8,
+        uint8: u8,
+        int16: i16,
+        uint16: u16,
+        int32: i32,
+        uint32: u32,
+        int64: i64,
+        uint64: u64,
+        int128: i128,
+        uint128: u128,
+        int_size: isize,
+        uint_size: core::primitive::usize,
+        float32: f32,
+        float64: f64,
+        address: Ipv4Addr,
+        labels: Vec<&'static str>,
+        parent: Option<std::path::PathBuf>,
+    }
+
+    let primitives = dslPrimitivesDefault();
+    assert_eq!(primitives.string_get(), "");
+    assert_eq!(primitives.static_str_get(), "");
+    assert_eq!(primitives.path_get(), Path::new("target"));
+    assert!(!primitives.boolean_get());
+    assert_eq!(*primitives.character_get(), '\0');
+    assert_eq!(*primitives.int8_get(), 0);
+    assert_eq!(*primitives.uint128_get(), 0);
+    assert_eq!(*primitives.uint_size_get(), 0);
+    assert_eq!(*primitives.float64_get(), 0.0);
+    assert_eq!(*primitives.address_get(), Ipv4Addr::LOCALHOST);
+    assert_eq!(primitives.parent_get(), None);
+
+    let primitives = dslPrimitives(|o| {
+        o.string("name")
+            .static_str("label")
+            .path(Path::new("src"))
+            .boolean(true)
+            .character('x')
+            .int8(-8)
+            .uint8(8)
+            .int16(-16)
+            .uint16(16)
+            .int32(-32)
+            .uint32(32)
+            .int64(-64)
+            .uint64(64)
+            .int128(-128)
+            .uint128(128)
+            .int_size(-1)
+            .uint_size(1)
+            .float32(3.2)
+            .float64(6.4)
+            .address([10, 0, 0, 1])
+            .extend_labels(&["a", "b"])
+            .parent(Path::new("/"));
+    });
+    assert_eq!(primitives.string_get(), "name");
+    assert_eq!(primitives.static_str_get(), "label");
+    assert_eq!(primitives.path_get(), Path::new("src"));
+    assert!(primitives.boolean_get());
+    assert_eq!(*primitives.character_get(), 'x');
+    assert_eq!(*primitives.int8_get(), -8);
+    assert_eq!(*primitives.uint8_get(), 8);
+    assert_eq!(*primitives.int16_get(), -16);
+    assert_eq!(*primitives.uint16_get(), 16);
+    assert_eq!(*primitives.int32_get(), -32);
+    assert_eq!(*primitives.uint32_get(), 32);
+    assert_eq!(*primitives.int64_get(), -64);
+    assert_eq!(*primitives.uint64_get(), 64);
+    assert_eq!(*primitives.int128_get(), -128);
+    assert_eq!(*primitives.uint128_get(), 128);
+    assert_eq!(*primitives.int_size_get(), -1);
+    assert_eq!(*primitives.uint_size_get(), 1);
+    assert_eq!(*primitives.float32_get(), 3.2);
+    assert_eq!(*primitives.float64_get(), 6.4);
+    assert_eq!(*primitives.address_get(), Ipv4Addr::new(10, 0, 0, 1));
+    assert_eq!(primitives.labels_get(), ["a", "b"]);
+    assert_eq!(primitives.parent_get(), Some(Path::new("/")));
+
+    let yaml = serde_yaml::to_string(&primitives).unwrap();
+    let read: DslPrimitivesImpl = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(read, primitives);
+}
+
+#[test]
+fn dsl_item_explicitly_set() {
+    use ddd_model::item::{dslAttribute, DslAttributeGet, DslAttributeImpl, DslAttributeSet, DslItemGet, DslItemSet};
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    struct Column {
+        name: String,
+        #[dsl(default = "varchar")]
+        sql_type: String,
+        size: Option<u32>,
+        tags: Vec<String>,
+    }
+
+    let mut column = dslColumn(|o| {
+        o.name("code").add_tags("key");
+    });
+    assert!(column.is_name_set());
+    assert!(!column.is_sql_type_set());
+    assert!(!column.is_size_set());
+    assert!(column.is_tags_set());
+    assert_eq!(column.explicitly_set_fields(), ["name", "tags"]);
+
+    let yaml = serde_yaml::to_string(&column).unwrap();
+    assert_eq!(yaml, "name: code\ntags:\n- key\n");
+    let loaded: DslColumnImpl = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(loaded, column);
+    assert_eq!(loaded.sql_type_get(), "varchar");
+
+    column.sql_type("char").reset_name().reset_tags();
+    assert_eq!(column.name_get(), "");
+    assert_eq!(column.explicitly_set_fields(), ["sql_type"]);
+    assert_eq!(serde_yaml::to_string(&column).unwrap(), "sql_type: char\n");
+
+    let mut attribute = dslAttribute(|o| {
+        o.name("id").nullable(true);
+    });
+    assert_eq!(attribute.explicitly_set_fields(), ["name", "nullable"]);
+    attribute.reset_name();
+    assert!(!attribute.is_name_set());
+    assert!(attribute.is_nullable_set());
+    let yaml = serde_yaml::to_string(&attribute).unwrap();
+    assert_eq!(yaml, "nullable: true\n");
+    let loaded: DslAttributeImpl = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(loaded, attribute);
+}
+
+#[test]
+fn dsl_item_merge_from() {
+    use ddd_model::item::{dslAttribute, DslAttributeGet, DslAttributeImpl, DslAttributeSet, DslItemGet, DslItemSet};
+
+    let template = dslAttribute(|o| {
+        o.name("code").desc("Business key").nullable(false).multi(true);
+    });
+
+    let mut order_code = dslAttribute(|o| {
+        o.name("order_code").nullable(true);
+    });
+    order_code.merge_from(&template);
+    assert_eq!(order_code.name_get(), "order_code");
+    assert_eq!(order_code.desc_get(), "Business key");
+    assert!(order_code.nullable_get());
+    assert!(order_code.multi_get());
+    assert_eq!(order_code.derived_from(), Some(&template));
+
+    let customer_code = dslAttribute(|o| {
+        o.name("customer_code");
+    })
+    .overlay(&template);
+    assert_eq!(customer_code.name_get(), "customer_code");
+    assert!(!customer_code.nullable_get());
+    assert!(customer_code.is_nullable_set());
+    assert_eq!(customer_code.derived_from(), Some(&template));
+    assert_eq!(template.derived_from(), None);
+
+    // The merged fields are written, not the template.
+    let yaml = serde_yaml::to_string(&customer_code).unwrap();
+    assert_eq!(yaml, "name: customer_code\ndesc: Business key\nnullable: false\nmulti: true\n");
+    let loaded: DslAttributeImpl = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(loaded.derived_from(), None);
+    assert_eq!(loaded, customer_code);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{create_node, name};
+
+#[test]
+fn depth_and_breadth_first() {
+    let root = create_node(2, 2);
+    let dfs = root.borrow().dfs().map(|(node, depth)| (name(&node), depth)).collect::<Vec<_>>();
+    assert_eq!(
+        dfs,
+        [
+            ("Node 0".to_string(), 0),
+            ("Node 0.0".to_string(), 1),
+            ("Node 0.0.0".to_string(), 2),
+            ("Node 0.0.1".to_string(), 2),
+            ("Node 0.1".to_string(), 1),
+            ("Node 0.1.0".to_string(), 2),
+            ("Node 0.1.1".to_string(), 2),
+        ]
+    );
+
+    let bfs = root.borrow().bfs().map(|(node, _)| name(&node)).collect::<Vec<_>>();
+    assert_eq!(bfs, ["Node 0", "Node 0.0", "Node 0.1", "Node 0.0.0", "Node 0.0.1", "Node 0.1.0", "Node 0.1.1"]);
+
+    let descendants = root.borrow().descendants().map(|(node, depth)| (name(&node), depth)).collect::<Vec<_>>();
+    assert_eq!(descendants.len(), 6);
+    assert_eq!(descendants[0], ("Node 0.0".to_string(), 1));
+
+    // Iterator adapters stop early with a value.
+    let leaf = root.borrow().dfs().find(|(node, _)| name(node) == "Node 0.1.0").map(|(node, _)| node).unwrap();
+    assert_eq!(leaf.borrow().ancestors().map(|(node, depth)| (name(&node), depth)).collect::<Vec<_>>(), [
+        ("Node 0.1".to_string(), 1),
+        ("Node 0".to_string(), 2)
+    ]);
+    assert_eq!(leaf.borrow().siblings().map(|(node, _)| name(&node)).collect::<Vec<_>>(), ["Node 0.1.1"]);
+    assert_eq!(root.borrow().siblings().count(), 0);
+    assert_eq!(root.borrow().ancestors().count(), 0);
+}
+
+#[test]
+fn pruning() {
+    let root = create_node(3, 2);
+
+    let mut visited = Vec::new();
+    let mut dfs = root.borrow().dfs();
+    while let Some((node, depth)) = dfs.next() {
+        let name = name(&node);
+        if name == "Node 0.0" || depth == 2 {
+            dfs.skip_subtree();
+        }
+        if name == "Node 0.1.1" {
+            dfs.stop();
+        }
+        visited.push(name);
+    }
+    assert_eq!(visited, ["Node 0", "Node 0.0", "Node 0.1", "Node 0.1.0", "Node 0.1.1"]);
+
+    let mut visited = Vec::new();
+    let mut bfs = root.borrow().bfs();
+    while let Some((node, _)) = bfs.next() {
+        if name(&node) == "Node 0.0" {
+            bfs.skip_subtree();
+        }
+        visited.push(name(&node));
+    }
+    assert_eq!(visited.len(), 9);
+    assert!(visited.iter().all(|name| !name.starts_with("Node 0.0.")));
+}
+
+#[test]
+fn iterate_while_borrowed_mutably() {
+    let root = create_node(2, 2);
+
+    // The node the iteration starts from isn't borrowed again.
+    let guard = root.borrow_mut();
+    let depths = guard.dfs().map(|(_, depth)| depth).collect::<Vec<_>>();
+    assert_eq!(depths, [0, 1, 2, 2, 1, 2, 2]);
+    assert_eq!(guard.bfs().map(|(_, depth)| depth).collect::<Vec<_>>(), [0, 1, 1, 2, 2, 2, 2]);
+    let descendants = guard.descendants().map(|(node, _)| name(&node)).collect::<Vec<_>>();
+    assert_eq!(descendants[0], "Node 0.0");
+    drop(guard);
+
+    // Nor is a yielded node when the iterator moves on.
+    let mut dfs = root.borrow().descendants();
+    let (first, _) = dfs.next().unwrap();
+    let mut first = first.borrow_mut();
+    first.add_alias("here", "self");
+    assert_eq!(dfs.next().map(|(node, depth)| (name(&node), depth)), Some(("Node 0.0.0".to_string(), 2)));
+    drop(first);
+}
@@ -0,0 +1,87 @@
+use ddd_model::item::{dslAttribute, dslItem, DslItemSet, DslNamingSet};
+use ddd_model::naming::{
+    camel_case, kebab_case, pascal_case, pluralize, screaming_case, singularize, snake_case, DslItemNames,
+};
+use ddd_model::node::Node;
+
+#[test]
+fn cases() {
+    for name in ["OrderItem", "orderItem", "order_item", "order-item", "ORDER_ITEM", "Order Item"] {
+        assert_eq!(snake_case(name), "order_item", "{name}");
+        assert_eq!(camel_case(name), "orderItem", "{name}");
+        assert_eq!(pascal_case(name), "OrderItem", "{name}");
+        assert_eq!(kebab_case(name), "order-item", "{name}");
+        assert_eq!(screaming_case(name), "ORDER_ITEM", "{name}");
+    }
+
+    assert_eq!(snake_case("HTTPServer"), "http_server");
+    assert_eq!(pascal_case("parseURL"), "ParseUrl");
+    assert_eq!(snake_case("Address2Line"), "address2_line");
+    assert_eq!(camel_case(""), "");
+}
+
+#[test]
+fn plurals() {
+    for (singular, plural) in [
+        ("Order", "Orders"),
+        ("OrderCategory", "OrderCategories"),
+        ("Address", "Addresses"),
+        ("Box", "Boxes"),
+        ("Batch", "Batches"),
+        ("Day", "Days"),
+        ("Person", "People"),
+        ("order_child", "order_children"),
+        ("Shelf", "Shelves"),
+        ("Money", "Money"),
+        ("ORDER_ITEM", "ORDER_ITEMS"),
+        ("Criterion", "Criteria"),
+        ("Status", "Statuses"),
+        ("Bus", "Buses"),
+        ("Class", "Classes"),
+        ("Warehouse", "Warehouses"),
+        ("Excuse", "Excuses"),
+        ("Movie", "Movies"),
+        ("Cookie", "Cookies"),
+        ("Quiz", "Quizzes"),
+        ("Fez", "Fezzes"),
+        ("Waltz", "Waltzes"),
+    ] {
+        assert_eq!(pluralize(singular), plural);
+        assert_eq!(singularize(plural), singular);
+    }
+
+    assert_eq!(singularize("Status"), "Status");
+    assert_eq!(singularize("Class"), "Class");
+}
+
+#[test]
+fn item_names() {
+    let item = dslItem(|o| {
+        o.name("OrderCategory");
+    });
+    assert_eq!(item.snake_case_name(), "order_category");
+    assert_eq!(item.camel_case_name(), "orderCategory");
+    assert_eq!(item.pascal_case_name(), "OrderCategory");
+    assert_eq!(item.kebab_case_name(), "order-category");
+    assert_eq!(item.screaming_case_name(), "ORDER_CATEGORY");
+    assert_eq!(item.plural_name(), "OrderCategories");
+    assert_eq!(item.singular_name(), "OrderCategory");
+}
+
+#[test]
+fn item_name_overrides() {
+    let attribute = dslAttribute(|o| {
+        o.name("Status").naming_with(|o| {
+            o.plural("StatusValues").snake_case("state");
+        });
+    });
+    assert_eq!(attribute.plural_name(), "StatusValues");
+    assert_eq!(attribute.snake_case_name(), "state");
+    assert_eq!(attribute.kebab_case_name(), "status");
+
+    // The overrides are part of the model, every generator reading it names the item alike.
+    let root = Node::new(Box::new(attribute));
+    let yaml = root.borrow().serialize_to_yaml().unwrap();
+    assert!(yaml.contains("plural: StatusValues"));
+    assert_eq!(root.borrow().item().plural_name(), "StatusValues");
+}
@@ -1,6 +1,14 @@
+mod common;
+
+use common::{create_node, item, NodeRef};
+use ddd_model::item::{dslAttribute, DslAttributeGet, DslAttributeSet, DslItemSet};
+use ddd_model::node::{EditError, Node};
+use std::cell::RefCell;
+use std::rc::Rc;
+
 #[test]
 fn node() {
-    let root = create_tree(3, 3);
+    let root = create_node(3, 3);
     /*
     root.borrow().traverse_up(
         |node| println!("Visiting node: {:?}", node.item.name()),
@@ -11,47 +19,103 @@ fn node() {
 
      */
 
-    root.borrow().write_to_yaml_file("test.yaml").unwrap();
+    let file_path = std::env::temp_dir().join("ddd_model_node.yaml");
+    root.borrow().write_to_yaml_file(file_path.to_str().unwrap()).unwrap();
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct SimpleItem {
-    name: String,
-    namespace: String,
-}
+#[test]
+fn node_yaml_round_trip() {
+    let root = create_node(3, 3);
+    let yaml = root.borrow().serialize_to_yaml().unwrap();
 
-#[typetag::serde]
-impl Item for SimpleItem {
-    fn name(&self) -> String {
-        self.name.clone()
-    }
-    fn namespace(&self) -> String {
-        self.namespace.clone()
-    }
+    let loaded = Node::load_yaml(&yaml).unwrap();
+    assert_eq!(loaded.borrow().serialize_to_yaml().unwrap(), yaml);
+
+    let file_path = std::env::temp_dir().join("ddd_model_node_round_trip.yaml");
+    let file_path = file_path.to_str().unwrap();
+    root.borrow().write_to_yaml_file(file_path).unwrap();
+    let read = Node::read_from_yaml_file(file_path).unwrap();
+    assert_eq!(read.borrow().serialize_to_yaml().unwrap(), yaml);
 }
 
-fn create_tree(depth: usize, breadth: usize) -> Rc<RefCell<Node>> {
-    let node = Rc::new(RefCell::new(Node {
-        item: Box::new(SimpleItem { name: format!("Node 0"), namespace: format!("Namespace 0") }),
-        me: None,
-        parent: None,
-        children: Vec::new(),
-    }));
+#[test]
+fn node_yaml_relinks_parents() {
+    let root = Node::new(item("shop"));
+    let order = root.borrow_mut().add_child(item("Order"));
+    order.borrow_mut().add_child(Box::new(dslAttribute(|o| {
+        o.nullable(true).name("total");
+    })));
+    let yaml = root.borrow().serialize_to_yaml().unwrap();
+
+    let loaded = Node::from_reader(yaml.as_bytes()).unwrap();
+    let total = loaded
+        .borrow()
+        .find_child(&|node| node.item().name_get() == "total")
+        .unwrap();
+    let total = total.borrow();
+    let attribute = total.item().as_any().downcast_ref::<ddd_model::item::DslAttributeImpl>().unwrap();
+    assert!(attribute.nullable_get());
+
+    let parent = total.find_parent(|item| item.name_get() == "Order").unwrap();
+    assert_eq!(parent.upgrade().unwrap().borrow().item().name_get(), "Order");
+    let shop = total.find_parent(|item| item.name_get() == "shop").unwrap();
+    assert!(Rc::ptr_eq(&shop.upgrade().unwrap(), &loaded));
 
-    create_children(Rc::clone(&node), depth, breadth, 1);
+    let visited = RefCell::new(Vec::new());
+    total.traverse_up(|node| visited.borrow_mut().push(node.item().name_get().to_owned()), |_| false);
+    assert_eq!(visited.into_inner(), ["total", "Order", "shop"]);
 
-    node
+    // Nodes added after loading are linked as well.
+    let child = loaded.borrow_mut().add_child(item("Customer"));
+    assert!(child.borrow().find_parent(|item| item.name_get() == "shop").is_some());
 }
 
-fn create_children(parent: Rc<RefCell<Node>>, depth: usize, breadth: usize, current_depth: usize) {
-    if current_depth > depth {
-        return;
-    }
-
-    for i in 0..breadth {
-        let child = parent.borrow_mut().add_child(
-            Box::new(SimpleItem { name: format!("Node {}", current_depth * breadth + i), namespace: format!("Namespace {}", current_depth * breadth + i) })
-        );
-        create_children(child, depth, breadth, current_depth + 1);
-    }
-}
\ No newline at end of file
+#[test]
+fn node_editing() {
+    let names = |node: &NodeRef| {
+        node.borrow().children().iter().map(|child| child.borrow().item().name_get().to_owned()).collect::<Vec<_>>()
+    };
+    let root = Node::new(item("shop"));
+    let order = root.borrow_mut().add_child(item("Order"));
+    let customer = root.borrow_mut().add_child(item("Customer"));
+    let money = root.borrow_mut().insert_child_at(1, item("Money")).unwrap();
+    assert_eq!(names(&root), ["Order", "Money", "Customer"]);
+    assert!(Rc::ptr_eq(&money.borrow().parent().unwrap(), &root));
+    assert_eq!(
+        root.borrow_mut().insert_child_at(4, item("Invoice")).err(),
+        Some(EditError::IndexOutOfBounds { index: 4, len: 3 })
+    );
+
+    root.borrow_mut().swap(0, 2).unwrap();
+    assert_eq!(names(&root), ["Customer", "Money", "Order"]);
+    assert!(root.borrow_mut().swap(0, 3).is_err());
+
+    // Moving a node carries its subtree, and a node can't be moved below itself.
+    let total = order.borrow_mut().add_child(item("total"));
+    Node::reparent(&order, &customer).unwrap();
+    assert_eq!(names(&root), ["Customer", "Money"]);
+    assert_eq!(names(&customer), ["Order"]);
+    assert!(Rc::ptr_eq(&order.borrow().parent().unwrap(), &customer));
+    assert!(total.borrow().find_parent(|item| item.name_get() == "Customer").is_some());
+    assert_eq!(Node::reparent(&customer, &total), Err(EditError::Cycle));
+    assert_eq!(Node::reparent(&customer, &customer), Err(EditError::Cycle));
+    assert_eq!(names(&customer), ["Order"]);
+
+    Node::detach(&order);
+    assert!(order.borrow().parent().is_none());
+    assert!(names(&customer).is_empty());
+    Node::detach(&order);
+    Node::reparent(&order, &root).unwrap();
+    assert_eq!(names(&root), ["Customer", "Money", "Order"]);
+
+    let removed = root.borrow_mut().remove_child(1).unwrap();
+    assert!(Rc::ptr_eq(&removed, &money));
+    assert!(money.borrow().parent().is_none());
+    assert_eq!(names(&root), ["Customer", "Order"]);
+    assert!(root.borrow_mut().remove_child(2).is_err());
+
+    let previous = order.borrow_mut().replace_item(item("PurchaseOrder"));
+    assert_eq!(previous.name_get(), "Order");
+    assert_eq!(names(&root), ["Customer", "PurchaseOrder"]);
+    assert!(total.borrow().find_parent(|item| item.name_get() == "PurchaseOrder").is_some());
+}
@@ -0,0 +1,115 @@
+use ddd_model::item::{dslItem, DslItemGet, DslItemSet};
+use ddd_model::node::Node;
+use ddd_model::reference::{Ref, RefError};
+use ddd_runtime::reflect::{DynValue, Reflect};
+use ddd_runtime::AsDslItem;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+#[dsl(extends = ddd_model::item::Item)]
+struct Value {
+    immutable: bool,
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+#[dsl(extends = ddd_model::item::Item)]
+struct Property {
+    of_type: Ref<DslValueImpl>,
+    see_also: Vec<Ref<DslValueImpl>>,
+}
+
+fn create_model(of_type: &str) -> (Rc<RefCell<Node>>, Rc<RefCell<Node>>) {
+    let root = Node::new(Box::new(dslItem(|o| {
+        o.name("shop");
+    })));
+    let order = root.borrow_mut().add_child(Box::new(dslItem(|o| {
+        o.name("order");
+    })));
+    order.borrow_mut().add_child(Box::new(dslValue(|o| {
+        o.immutable(true).name("Money");
+    })));
+    let total = order.borrow_mut().add_child(Box::new(dslProperty(|o| {
+        o.of_type(of_type).name("total");
+    })));
+    (root, total)
+}
+
+fn of_type(node: &Rc<RefCell<Node>>) -> Ref<DslValueImpl> {
+    let node = node.borrow();
+    let property = node.item().as_any().downcast_ref::<DslPropertyImpl>().unwrap();
+    property.of_type_get().clone()
+}
+
+#[test]
+fn resolve_ref() {
+    let (root, total) = create_model("shop::order::Money");
+    let money = total.borrow().resolve_ref(&of_type(&total)).unwrap();
+    assert!(money.borrow().immutable_get());
+    assert_eq!(money.borrow().name_get(), "Money");
+
+    money.borrow_mut().immutable(false);
+    let money = root.borrow().resolve_ref(&Ref::<DslValueImpl>::new("shop::order::Money")).unwrap();
+    assert!(!money.borrow().immutable_get());
+    assert!(root.borrow().check_references().is_empty());
+
+    // Relative references resolve from the item holding them.
+    let (root, total) = create_model("..::Money");
+    assert_eq!(total.borrow().resolve_ref(&of_type(&total)).unwrap().borrow().name_get(), "Money");
+    assert!(root.borrow().check_references().is_empty());
+    assert!(root.borrow().resolve_ref(&Ref::<DslValueImpl>::new("self::order::Money")).is_ok());
+}
+
+#[test]
+fn dotted_names() {
+    let root = Node::new(Box::new(dslItem(|o| {
+        o.name("shop");
+    })));
+    let version = root.borrow_mut().add_child(Box::new(dslItem(|o| {
+        o.name("v1.2");
+    })));
+    version.borrow_mut().add_child(Box::new(dslValue(|o| {
+        o.name("Money");
+    })));
+
+    let money = root.borrow().resolve_ref(&Ref::<DslValueImpl>::new("shop::v1.2::Money")).unwrap();
+    assert_eq!(money.borrow().name_get(), "Money");
+    assert_eq!(Ref::<DslValueImpl>::new("shop::v1.2::Money").segments().collect::<Vec<_>>(), ["shop", "v1.2", "Money"]);
+}
+
+#[test]
+fn dangling_references() {
+    let (root, total) = create_model("shop::order::Mony");
+    let err = total.borrow().resolve_ref(&of_type(&total)).err().unwrap();
+    assert_eq!(err, RefError::Dangling { name: "shop::order::Mony".to_owned() });
+
+    let err = root.borrow().resolve_ref(&Ref::<DslValueImpl>::new("shop::order")).err().unwrap();
+    assert!(matches!(err, RefError::WrongKind { found: "Item", .. }));
+
+    let diagnostics = root.borrow().check_references();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "Property.of_type: total: dangling reference, no item is named `shop::order::Mony`"
+    );
+}
+
+#[test]
+fn serialize_references() {
+    let property = dslProperty(|o| {
+        o.of_type("shop::order::Money").add_see_also("shop::Currency").name("total");
+    });
+    let yaml = serde_yaml::to_string(&property).unwrap();
+    assert!(yaml.contains("of_type: shop::order::Money"));
+    assert_eq!(serde_yaml::from_str::<DslPropertyImpl>(&yaml).unwrap(), property);
+
+    let references = property.references();
+    let names = references
+        .iter()
+        .map(|(field, reference)| (*field, reference.name()))
+        .collect::<Vec<_>>();
+    assert_eq!(names, [("of_type", "shop::order::Money"), ("see_also", "shop::Currency")]);
+    assert_eq!(property.get_field("of_type"), Ok(DynValue::from("shop::order::Money")));
+}
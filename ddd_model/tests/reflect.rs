@@ -0,0 +1,89 @@
+use ddd_runtime::AsDslItem;
+use ddd_model::item::{dslAttribute, DslAttributeImpl, DslItemGet, DslItemSet};
+use ddd_runtime::reflect::{DynValue, FieldKind, Reflect, ReflectError};
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Column {
+    #[dsl(required, doc = "Name of the column.")]
+    name: String,
+    #[dsl(default = "varchar")]
+    sql_type: String,
+    size: Option<u32>,
+    tags: Vec<String>,
+    id: Box<DslAttributeImpl>,
+}
+
+#[test]
+fn reflect_descriptor() {
+    let descriptor = &DslColumnImpl::DESCRIPTOR;
+    assert_eq!(descriptor.name, "Column");
+    assert_eq!(descriptor.extends, None);
+
+    let names = descriptor.fields.iter().map(|field| field.name).collect::<Vec<_>>();
+    assert_eq!(names, ["name", "sql_type", "size", "tags", "id"]);
+    let kinds = descriptor.fields.iter().map(|field| field.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [FieldKind::Scalar, FieldKind::Scalar, FieldKind::Option, FieldKind::List, FieldKind::Box]
+    );
+
+    let name = descriptor.field("name").unwrap();
+    assert_eq!(name.ty, "String");
+    assert_eq!(name.doc, Some("Name of the column."));
+    assert!(name.required);
+    assert_eq!(descriptor.field("sql_type").unwrap().default, Some("varchar"));
+    assert_eq!(descriptor.field("size").unwrap().ty, "Option<u32>");
+
+    let attribute = &DslAttributeImpl::DESCRIPTOR;
+    assert_eq!(attribute.extends.map(|extends| extends.name), Some("Item"));
+    assert_eq!(attribute.field("namespace").unwrap().kind, FieldKind::Scalar);
+    let all_fields = attribute.all_fields();
+    assert_eq!(all_fields.first().unwrap().name, "name");
+    assert_eq!(all_fields.last().unwrap().name, "name_non_fluent");
+}
+
+#[test]
+fn reflect_get_set_field() {
+    let mut column = dslColumn(|o| {
+        o.name("code").add_tags("key");
+    });
+
+    assert_eq!(column.get_field("name"), Ok(DynValue::from("code")));
+    assert_eq!(column.get_field("sql_type"), Ok(DynValue::from("varchar")));
+    assert_eq!(column.get_field("size"), Ok(DynValue::None));
+    assert_eq!(column.get_field("tags"), Ok(DynValue::from(vec!["key"])));
+    assert_eq!(column.get_field("id"), Ok(DynValue::None));
+    assert_eq!(
+        column.get_field("unknown"),
+        Err(ReflectError::UnknownField("unknown".to_owned()))
+    );
+
+    column.set_field("size", DynValue::UInt(12)).unwrap();
+    column.set_field("tags", DynValue::from(vec!["a", "b"])).unwrap();
+    column.set_field("name", DynValue::None).unwrap();
+    column
+        .set_field("id", DynValue::Map(vec![("name".to_owned(), DynValue::from("id"))]))
+        .unwrap();
+    assert_eq!(column.size_get(), Some(&12));
+    assert_eq!(column.tags_get(), ["a", "b"]);
+    assert!(!column.is_name_set());
+    assert_eq!(column.id_get().as_ref().unwrap().name_get(), "id");
+    assert!(matches!(
+        column.set_field("size", DynValue::from("large")),
+        Err(ReflectError::InvalidValue { .. })
+    ));
+}
+
+#[test]
+fn reflect_dyn_item() {
+    let mut item: Box<dyn DslItemGet> = Box::new(dslAttribute(|o| {
+        o.name("id");
+    }));
+
+    assert_eq!(item.descriptor().name, "Attribute");
+    item.set_field("nullable", DynValue::Bool(true)).unwrap();
+    item.set_field("namespace", DynValue::from("shop")).unwrap();
+    assert_eq!(item.get_field("nullable"), Ok(DynValue::Bool(true)));
+    assert_eq!(item.namespace_get(), "shop");
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{item, NodeRef};
+use ddd_model::node::Node;
+use std::rc::Rc;
+
+fn name(node: Option<NodeRef>) -> Option<String> {
+    node.as_ref().map(common::name)
+}
+
+/// shop { order { Order { id }, Money }, billing { Invoice } }
+fn create_model() -> (NodeRef, NodeRef, NodeRef) {
+    let root = Node::new(item("shop"));
+    let order = root.borrow_mut().add_child(item("order"));
+    let entity = order.borrow_mut().add_child(item("Order"));
+    let id = entity.borrow_mut().add_child(item("id"));
+    order.borrow_mut().add_child(item("Money"));
+    let billing = root.borrow_mut().add_child(item("billing"));
+    billing.borrow_mut().add_child(item("Invoice"));
+    (root, entity, id)
+}
+
+#[test]
+fn qualified_names() {
+    let (root, entity, id) = create_model();
+    assert_eq!(root.borrow().qualified_name(), "shop");
+    assert_eq!(entity.borrow().qualified_name(), "shop::order::Order");
+    assert_eq!(id.borrow().qualified_name(), "shop::order::Order::id");
+
+    let resolved = root.borrow().resolve("shop::order::Order::id").unwrap();
+    assert!(Rc::ptr_eq(&resolved, &id));
+    assert!(Rc::ptr_eq(&id.borrow().resolve("shop").unwrap(), &root));
+    assert_eq!(name(root.borrow().resolve("shop::order::Customer")), None);
+    assert_eq!(name(root.borrow().resolve("order::Order")), None);
+}
+
+#[test]
+fn relative_paths() {
+    let (_root, entity, id) = create_model();
+    assert_eq!(name(entity.borrow().resolve("..::Money")), Some("Money".to_string()));
+    assert_eq!(name(entity.borrow().resolve("self::id")), Some("id".to_string()));
+    assert_eq!(name(id.borrow().resolve("..::..::..::billing::Invoice")), Some("Invoice".to_string()));
+    assert_eq!(name(id.borrow().resolve("..::..::..::..")), None);
+}
+
+#[test]
+fn aliases() {
+    let (root, entity, id) = create_model();
+    root.borrow_mut().add_alias("bill", "shop::billing");
+    entity.borrow_mut().add_alias("money", "..::Money");
+    assert_eq!(name(id.borrow().resolve("bill::Invoice")), Some("Invoice".to_string()));
+    assert_eq!(name(id.borrow().resolve("money")), Some("Money".to_string()));
+    // Aliases only apply below the node they are added to.
+    assert_eq!(name(root.borrow().resolve("money")), None);
+
+    // The nearest alias wins.
+    entity.borrow_mut().add_alias("bill", "shop::order");
+    assert_eq!(name(id.borrow().resolve("bill::Money")), Some("Money".to_string()));
+    assert_eq!(name(root.borrow().resolve("bill::Invoice")), Some("Invoice".to_string()));
+
+    let yaml = root.borrow().serialize_to_yaml().unwrap();
+    let loaded = Node::load_yaml(&yaml).unwrap();
+    let loaded_id = loaded.borrow().resolve("shop::order::Order::id").unwrap();
+    assert_eq!(name(loaded_id.borrow().resolve("money")), Some("Money".to_string()));
+    assert!(root.borrow().to_dsl_source().contains("add_alias(\"money\", \"..::Money\");"));
+}
+
+#[test]
+fn resolve_while_borrowed_mutably() {
+    let (root, entity, id) = create_model();
+    let mut guard = entity.borrow_mut();
+    guard.add_alias("here", "self");
+    assert!(Rc::ptr_eq(&guard.resolve("shop::order::Order").unwrap(), &entity));
+    assert!(Rc::ptr_eq(&guard.resolve("..::Order::id").unwrap(), &id));
+    assert!(Rc::ptr_eq(&guard.resolve("here::id").unwrap(), &id));
+    assert_eq!(name(guard.resolve("..::Money")), Some("Money".to_string()));
+    drop(guard);
+
+    let guard = root.borrow_mut();
+    assert!(Rc::ptr_eq(&guard.resolve("shop").unwrap(), &root));
+    assert!(Rc::ptr_eq(&guard.resolve("self::order::Order").unwrap(), &entity));
+}
@@ -0,0 +1,166 @@
+use ddd_model::item::{dslAttribute, dslItem, dslItemDefault, DslAttributeSet, DslItemSet};
+use ddd_model::node::Node;
+use ddd_runtime::AsDslItem;
+use std::path::PathBuf;
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Column {
+    name: String,
+    width: Option<u16>,
+    ratio: f64,
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Table {
+    name: String,
+    path: PathBuf,
+    separator: char,
+    label: &'static str,
+    columns: Vec<DslColumnImpl>,
+    key: Box<DslColumnImpl>,
+}
+
+#[test]
+fn item_source() {
+    let attribute = dslAttribute(|o| {
+        o.nullable(true).name("total \"net\"");
+    });
+    assert_eq!(
+        attribute.to_dsl_source(),
+        r#"dslAttribute(|o| {
+    o.name("total \"net\"");
+    o.nullable(true);
+})"#
+    );
+    assert_eq!(dslItemDefault().to_dsl_source(), "dslItemDefault()");
+}
+
+#[test]
+fn nested_item_source() {
+    let table = dslTable(|o| {
+        o.name("orders")
+            .path(std::path::Path::new("db/orders.csv"))
+            .separator(';')
+            .add_columns_with(|o| {
+                o.name("id").width(8);
+            })
+            .add_columns_with(|o| {
+                o.name("total").ratio(0.5);
+            })
+            .key(Box::new(dslColumn(|o| {
+                o.name("id");
+            })));
+    });
+
+    let source = table.to_dsl_source();
+    assert_eq!(
+        source,
+        r#"dslTable(|o| {
+    o.name("orders");
+    o.path(::std::path::Path::new("db/orders.csv"));
+    o.separator(';');
+    o.add_columns(dslColumn(|o| {
+        o.name("id");
+        o.width(8u16);
+    }));
+    o.add_columns(dslColumn(|o| {
+        o.name("total");
+        o.ratio(0.5f64);
+    }));
+    o.key(Box::new(dslColumn(|o| {
+        o.name("id");
+    })));
+})"#
+    );
+
+    // The printed source, pasted.
+    let printed = dslTable(|o| {
+        o.name("orders");
+        o.path(::std::path::Path::new("db/orders.csv"));
+        o.separator(';');
+        o.add_columns(dslColumn(|o| {
+            o.name("id");
+            o.width(8u16);
+        }));
+        o.add_columns(dslColumn(|o| {
+            o.name("total");
+            o.ratio(0.5f64);
+        }));
+        o.key(Box::new(dslColumn(|o| {
+            o.name("id");
+        })));
+    });
+    assert_eq!(printed, table);
+    assert_eq!(printed.path_get(), PathBuf::from("db/orders.csv"));
+}
+
+#[test]
+fn node_source() {
+    let root = Node::new(Box::new(dslItem(|o| {
+        o.name("shop");
+    })));
+    let order = root.borrow_mut().add_child(Box::new(dslItem(|o| {
+        o.name("Order");
+    })));
+    order.borrow_mut().add_child(Box::new(dslAttribute(|o| {
+        o.name("id");
+    })));
+    root.borrow_mut().add_child(Box::new(dslItemDefault()));
+
+    assert_eq!(
+        root.borrow().to_dsl_source(),
+        r#"{
+    let root = Node::new(Box::new(dslItem(|o| {
+        o.name("shop");
+    })));
+    let node_1 = root.borrow_mut().add_child(Box::new(dslItem(|o| {
+        o.name("Order");
+    })));
+    node_1.borrow_mut().add_child(Box::new(dslAttribute(|o| {
+        o.name("id");
+    })));
+    root.borrow_mut().add_child(Box::new(dslItemDefault()));
+    root
+}"#
+    );
+
+    // The printed source, pasted.
+    let printed = {
+        let root = Node::new(Box::new(dslItem(|o| {
+            o.name("shop");
+        })));
+        let node_1 = root.borrow_mut().add_child(Box::new(dslItem(|o| {
+            o.name("Order");
+        })));
+        node_1.borrow_mut().add_child(Box::new(dslAttribute(|o| {
+            o.name("id");
+        })));
+        root.borrow_mut().add_child(Box::new(dslItemDefault()));
+        root
+    };
+    assert_eq!(printed.borrow().to_dsl_source(), root.borrow().to_dsl_source());
+}
+
+#[test]
+fn type_map_source() {
+    use std::net::Ipv4Addr;
+
+    #[allow(dead_code)]
+    #[derive(AsDslItem)]
+    #[dsl(type_map(ty = Ipv4Addr, default = Ipv4Addr::LOCALHOST, setter = [u8; 4], into = Ipv4Addr::from(value), source = format!("{:?}", value.octets())))]
+    struct Host {
+        address: Ipv4Addr,
+        port: u16,
+        weight: f32,
+    }
+
+    let host = dslHost(|o| {
+        o.address([10, 0, 0, 1]).port(8080).weight(-1.0);
+    });
+    assert_eq!(
+        host.to_dsl_source(),
+        "dslHost(|o| {\n    o.address([10, 0, 0, 1]);\n    o.port(8080u16);\n    o.weight(-1.0f32);\n})"
+    );
+}
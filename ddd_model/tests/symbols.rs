@@ -0,0 +1,83 @@
+mod common;
+
+use common::{attribute, item};
+use ddd_model::item::{dslItem, DslAttributeImpl, DslItemSet};
+use ddd_model::node::Node;
+use ddd_model::tree::ModelTree;
+
+#[test]
+fn lookups() {
+    let mut tree = ModelTree::new(item("shop"));
+    let order = tree.add_child(tree.root(), item("Order"));
+    let id = tree.add_child(order, attribute("id"));
+    let total = tree.add_child(order, attribute("total"));
+
+    assert_eq!(tree.qualified_name(id), "shop::Order::id");
+    assert_eq!(tree.symbols().get("shop::Order::id"), Some(id));
+    assert_eq!(tree.symbols().get("shop::Order"), Some(order));
+    assert_eq!(tree.symbols().get("shop::Customer"), None);
+    assert_eq!(tree.symbols().of_kind("Attribute"), [id, total]);
+    assert_eq!(tree.symbols().of_kind("Item"), [tree.root(), order]);
+    assert!(tree.symbols().of_kind("Naming").is_empty());
+    assert!(tree.symbols().duplicates().is_empty());
+}
+
+#[test]
+fn renames_reindex_the_subtree() {
+    let mut tree = ModelTree::new(item("shop"));
+    let order = tree.add_child(tree.root(), item("Order"));
+    let id = tree.add_child(order, attribute("id"));
+
+    tree.item_mut(order).as_any_mut().downcast_mut::<ddd_model::item::DslItemImpl>().unwrap().name("PurchaseOrder");
+    assert_eq!(tree.symbols().get("shop::Order::id"), None);
+    assert_eq!(tree.symbols().get("shop::PurchaseOrder::id"), Some(id));
+    assert_eq!(tree.qualified_name(id), "shop::PurchaseOrder::id");
+
+    // Other edits leave the index alone.
+    tree.item_mut(id).as_any_mut().downcast_mut::<DslAttributeImpl>().unwrap().desc("key");
+    assert_eq!(tree.symbols().get("shop::PurchaseOrder::id"), Some(id));
+}
+
+#[test]
+fn duplicate_definitions() {
+    let root = Node::new(item("shop"));
+    let order = root.borrow_mut().add_child(item("Order"));
+    order.borrow_mut().add_child(attribute("id"));
+    order.borrow_mut().add_child(attribute("id"));
+    root.borrow_mut().add_child(item("Order"));
+    let customer = root.borrow_mut().add_child(item("Customer"));
+    customer.borrow_mut().add_child(attribute("id"));
+    // Items are defined in their namespace, wherever they are in the tree.
+    let billing = root.borrow_mut().add_child(item("billing"));
+    billing.borrow_mut().add_child(item("Order"));
+    billing.borrow_mut().add_child(Box::new(dslItem(|o| {
+        o.name("Invoice").namespace("billing");
+    })));
+
+    let messages = root.borrow().check_duplicates().iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Item.name: `shop::Order` is already defined",
+            "Item.name: `shop::Order` is already defined",
+            "Attribute.name: `shop::Order::id` is already defined",
+        ]
+    );
+
+    let (mut tree, _) = root.borrow().snapshot();
+    let tree = std::sync::Arc::get_mut(&mut tree).unwrap();
+    assert_eq!(tree.symbols().definitions("shop::Order").len(), 3);
+    assert_eq!(tree.symbols().definitions("shop::Order::id")[1].1, "Attribute");
+    assert_eq!(tree.symbols().definitions("billing::Invoice").len(), 1);
+    assert_eq!(tree.symbols().get("shop::billing::Invoice"), Some(tree.symbols().definitions("billing::Invoice")[0].0));
+
+    // Renaming one of them or moving it to another namespace resolves the conflict.
+    let (second, _) = tree.symbols().definitions("shop::Order")[1];
+    tree.item_mut(second).as_any_mut().downcast_mut::<ddd_model::item::DslItemImpl>().unwrap().name("Supplier");
+    assert_eq!(tree.symbols().duplicates().len(), 2);
+    assert_eq!(tree.symbols().get("shop::Supplier"), Some(second));
+    let (third, _) = tree.symbols().definitions("shop::Order")[1];
+    tree.item_mut(third).as_any_mut().downcast_mut::<ddd_model::item::DslItemImpl>().unwrap().namespace("billing");
+    assert_eq!(tree.symbols().duplicates().len(), 1);
+    assert_eq!(tree.symbols().definitions("billing::Order"), [(third, "Item")]);
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use common::{create_node, create_tree, item};
+use ddd_model::item::{DslItemGet, DslItemSet};
+use ddd_model::node::Node;
+use ddd_model::tree::{ModelTree, NodeId};
+
+fn name(tree: &ModelTree, id: NodeId) -> &str {
+    tree.item(id).name_get()
+}
+
+#[test]
+fn tree_traversal() {
+    let tree = create_tree(3, 2);
+    assert_eq!(tree.node_count(), 15);
+
+    let mut visited = Vec::new();
+    tree.traverse_down(tree.root(), |_, item| visited.push(item.name_get().to_owned()), |_, item| {
+        item.name_get() == "Node 0.1.0"
+    });
+    assert_eq!(
+        visited,
+        [
+            "Node 0",
+            "Node 0.0",
+            "Node 0.0.0",
+            "Node 0.0.0.0",
+            "Node 0.0.0.1",
+            "Node 0.0.1",
+            "Node 0.0.1.0",
+            "Node 0.0.1.1",
+            "Node 0.1",
+            "Node 0.1.1",
+            "Node 0.1.1.0",
+            "Node 0.1.1.1",
+        ]
+    );
+
+    let leaf = tree.find_child(tree.root(), |_, item| item.name_get() == "Node 0.1.1.0").unwrap();
+    let mut path = Vec::new();
+    tree.traverse_up(leaf, |id, _| path.push(id), |_, _| false);
+    assert_eq!(path.len(), 4);
+    assert_eq!(path.last(), Some(&tree.root()));
+
+    let parent = tree.find_parent(leaf, |item| item.name_get() == "Node 0.1").unwrap();
+    assert_eq!(tree.parent(tree.parent(leaf).unwrap()), Some(parent));
+    assert_eq!(tree.find_parent(leaf, |item| item.name_get() == "Node 0.0"), None);
+
+    let children = tree.filter_and_collect(parent, |item| item.name_get().ends_with(".1"));
+    assert_eq!(children.iter().map(|&id| name(&tree, id)).collect::<Vec<_>>(), ["Node 0.1.1"]);
+}
+
+#[test]
+fn tree_edit_items() {
+    let mut tree = create_tree(1, 2);
+    let child = tree.children(tree.root())[1];
+    {
+        let mut item = tree.item_mut(child);
+        let item = item.as_any_mut().downcast_mut::<ddd_model::item::DslItemImpl>().unwrap();
+        item.desc("second");
+    }
+    assert_eq!(tree.item(child).desc_get(), "second");
+    assert_eq!(child.index(), 2);
+}
+
+#[test]
+fn tree_yaml_matches_node() {
+    let tree = create_tree(3, 3);
+    let node = create_node(3, 3);
+    let yaml = tree.serialize_to_yaml().unwrap();
+    assert_eq!(yaml, node.borrow().serialize_to_yaml().unwrap());
+
+    // Trees written by either representation load into the other.
+    let loaded = ModelTree::load_yaml(&yaml).unwrap();
+    assert_eq!(loaded.node_count(), tree.node_count());
+    assert_eq!(loaded.serialize_to_yaml().unwrap(), yaml);
+    let leaf = loaded.find_child(loaded.root(), |_, item| item.name_get() == "Node 0.2.2.2").unwrap();
+    assert!(loaded.find_parent(leaf, |item| item.name_get() == "Node 0").is_some());
+
+    let file_path = std::env::temp_dir().join("ddd_model_tree.yaml");
+    let file_path = file_path.to_str().unwrap();
+    tree.write_to_yaml_file(file_path).unwrap();
+    let read = Node::read_from_yaml_file(file_path).unwrap();
+    assert_eq!(read.borrow().serialize_to_yaml().unwrap(), yaml);
+    let read = ModelTree::read_from_yaml_file(file_path).unwrap();
+    assert_eq!(read.serialize_to_yaml().unwrap(), yaml);
+}
+
+#[test]
+fn snapshot_shared_between_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ModelTree>();
+    assert_send_sync::<Box<dyn DslItemGet>>();
+
+    let node = create_node(3, 3);
+    let (snapshot, root) = node.borrow().snapshot();
+    assert_eq!(root, snapshot.root());
+    assert_eq!(snapshot.serialize_to_yaml().unwrap(), node.borrow().serialize_to_yaml().unwrap());
+
+    // Generators reading the same model in parallel.
+    let results = std::thread::scope(|scope| {
+        let generators = (0..4)
+            .map(|generator| {
+                let snapshot = std::sync::Arc::clone(&snapshot);
+                scope.spawn(move || {
+                    let mut names = Vec::new();
+                    snapshot.traverse_down(
+                        snapshot.root(),
+                        |_, item| names.push(format!("{generator}:{}", item.name_get())),
+                        |_, _| false,
+                    );
+                    names
+                })
+            })
+            .collect::<Vec<_>>();
+        generators.into_iter().map(|generator| generator.join().unwrap()).collect::<Vec<_>>()
+    });
+    assert_eq!(results.len(), 4);
+    for (generator, names) in results.iter().enumerate() {
+        assert_eq!(names.len(), 40);
+        assert_eq!(names[0], format!("{generator}:Node 0"));
+    }
+}
+
+#[test]
+fn snapshot_keeps_aliases() {
+    let root = Node::new(item("shop"));
+    let order = root.borrow_mut().add_child(item("order"));
+    let entity = order.borrow_mut().add_child(item("Order"));
+    order.borrow_mut().add_child(item("Money"));
+    let billing = root.borrow_mut().add_child(item("billing"));
+    billing.borrow_mut().add_child(item("Invoice"));
+    root.borrow_mut().add_alias("bill", "shop::billing");
+    entity.borrow_mut().add_alias("money", "..::Money");
+
+    let (snapshot, entity_id) = entity.borrow().snapshot();
+    assert_eq!(snapshot.symbols().get("shop::order::Order"), Some(entity_id));
+    for path in ["money", "bill::Invoice", "..::Money", "self", "shop::billing"] {
+        let resolved = entity.borrow().resolve(path).unwrap().borrow().qualified_name();
+        let id = snapshot.resolve(entity_id, path).unwrap();
+        assert_eq!(snapshot.qualified_name(id), resolved, "{path}");
+    }
+    assert_eq!(snapshot.resolve(snapshot.root(), "money"), None);
+
+    // Aliases are written in the same shape by both trees.
+    let yaml = root.borrow().serialize_to_yaml().unwrap();
+    assert_eq!(snapshot.serialize_to_yaml().unwrap(), yaml);
+    let loaded = ModelTree::load_yaml(&yaml).unwrap();
+    let entity_id = loaded.symbols().get("shop::order::Order").unwrap();
+    assert_eq!(loaded.resolve(entity_id, "money"), loaded.symbols().get("shop::order::Money"));
+}
+
+#[test]
+fn snapshot_of_a_subtree() {
+    let root = Node::new(item("shop"));
+    let order = root.borrow_mut().add_child(item("order"));
+    let entity = order.borrow_mut().add_child(item("Order"));
+    root.borrow_mut().add_child(item("billing"));
+
+    // The whole tree is copied, whichever node it is taken from.
+    let guard = order.borrow_mut();
+    let (snapshot, order_id) = guard.snapshot();
+    drop(guard);
+    assert_eq!(snapshot.item(snapshot.root()).name_get(), "shop");
+    assert_eq!(snapshot.qualified_name(order_id), "shop::order");
+    assert_eq!(snapshot.serialize_to_yaml().unwrap(), root.borrow().serialize_to_yaml().unwrap());
+    let (_, entity_id) = entity.borrow().snapshot();
+    assert_eq!(snapshot.qualified_name(entity_id), "shop::order::Order");
+}
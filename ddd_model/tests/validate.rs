@@ -0,0 +1,82 @@
+use ddd_runtime::AsDslItem;
+use ddd_runtime::validate::Diagnostic;
+
+fn no_spaces(value: &str) -> Result<(), String> {
+    if value.contains(' ') {
+        Err(format!("`{value}` must not contain spaces"))
+    } else {
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+#[derive(AsDslItem)]
+struct Column {
+    #[dsl(validate(min_len = 1, regex = "^[a-z_][a-z0-9_]*$"))]
+    name: String,
+    #[dsl(validate(range = "1..=4000"))]
+    size: Option<u32>,
+    #[dsl(validate(range = "0..=30"))]
+    scale: u8,
+    #[dsl(validate(min_len = 1), validate(regex = "^[a-z]+$"))]
+    tags: Vec<String>,
+    #[dsl(validate = no_spaces)]
+    comment: String,
+}
+
+#[test]
+fn validate() {
+    let mut column = dslColumnDefault();
+    column.name("code").size(12).add_tags("key").comment("business_key");
+    assert_eq!(column.validate(), []);
+
+    column
+        .name("Code")
+        .size(5000)
+        .scale(31)
+        .clear_tags()
+        .comment("business key");
+    let diagnostics = column.validate();
+    let messages = diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Column.name: must match `^[a-z_][a-z0-9_]*$`",
+            "Column.size: must be in 1..=4000",
+            "Column.scale: must be in 0..=30",
+            "Column.tags: must have at least 1 item",
+            "Column.comment: `business key` must not contain spaces",
+        ]
+    );
+    assert_eq!(
+        diagnostics[0],
+        Diagnostic::new("Column", "name", "must match `^[a-z_][a-z0-9_]*$`")
+    );
+
+    column.name("").reset_size().scale(0).add_tags("Key").comment("");
+    let messages = column.validate().iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Column.name: must be at least 1 character long",
+            "Column.name: must match `^[a-z_][a-z0-9_]*$`",
+            "Column.tags: must match `^[a-z]+$`",
+        ]
+    );
+}
+
+#[test]
+fn invalid_items_build() {
+    // Models read from outside data are built first and reported on afterwards.
+    let column = dslColumn(|o| {
+        o.add_tags("key");
+    });
+    let messages = column.validate().iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Column.name: must be at least 1 character long",
+            "Column.name: must match `^[a-z_][a-z0-9_]*$`",
+        ]
+    );
+}
@@ -0,0 +1,116 @@
+use ddd_model::item::{dslAttribute, dslItem, DslAttributeImpl, DslAttributeSet, DslItemGet, DslItemImpl, DslItemSet};
+use ddd_model::node::Node;
+use ddd_model::visit::{walk_children, walk_children_mut, DslVisitor, DslVisitorMut};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn create_model() -> Rc<RefCell<Node>> {
+    let root = Node::new(Box::new(dslItem(|o| {
+        o.name("shop");
+    })));
+    let order = root.borrow_mut().add_child(Box::new(dslItem(|o| {
+        o.name("Order");
+    })));
+    for name in ["id", "total"] {
+        order.borrow_mut().add_child(Box::new(dslAttribute(|o| {
+            o.name(name);
+        })));
+    }
+    root.borrow_mut().add_child(Box::new(dslAttribute(|o| {
+        o.name("version").nullable(true);
+    })));
+    root
+}
+
+#[derive(Default)]
+struct Names {
+    items: Vec<String>,
+    attributes: Vec<String>,
+}
+
+impl DslVisitor for Names {
+    fn visit_item(&mut self, node: &Node, item: &DslItemImpl) {
+        self.items.push(item.name_get().to_owned());
+        walk_children(self, node);
+    }
+
+    fn visit_attribute(&mut self, _node: &Node, item: &DslAttributeImpl) {
+        self.attributes.push(item.name_get().to_owned());
+    }
+}
+
+#[test]
+fn visit() {
+    let root = create_model();
+
+    let mut names = Names::default();
+    names.visit_node(&root.borrow());
+    assert_eq!(names.items, ["shop", "Order"]);
+    assert_eq!(names.attributes, ["id", "total", "version"]);
+}
+
+#[test]
+fn visit_default_recurses() {
+    struct Attributes(usize);
+
+    impl DslVisitor for Attributes {
+        fn visit_attribute(&mut self, node: &Node, _item: &DslAttributeImpl) {
+            self.0 += 1;
+            walk_children(self, node);
+        }
+    }
+
+    let root = create_model();
+    let mut attributes = Attributes(0);
+    attributes.visit_node(&root.borrow());
+    assert_eq!(attributes.0, 3);
+}
+
+#[test]
+fn visit_mut() {
+    struct Prefix;
+
+    impl DslVisitorMut for Prefix {
+        fn visit_attribute_mut(&mut self, node: &Rc<RefCell<Node>>, item: &mut DslAttributeImpl) {
+            let name = format!("attr_{}", item.name_get());
+            item.name(&name);
+            walk_children_mut(self, node);
+        }
+    }
+
+    let root = create_model();
+    Prefix.visit_node_mut(&root);
+
+    let mut names = Names::default();
+    names.visit_node(&root.borrow());
+    assert_eq!(names.items, ["shop", "Order"]);
+    assert_eq!(names.attributes, ["attr_id", "attr_total", "attr_version"]);
+}
+
+#[test]
+fn visit_mut_reads_the_tree() {
+    // Items are described by their place in the tree, which needs the ancestors
+    // of the visited node and the node itself.
+    struct Describe;
+
+    impl DslVisitorMut for Describe {
+        fn visit_item_mut(&mut self, node: &Rc<RefCell<Node>>, item: &mut DslItemImpl) {
+            item.desc(&node.borrow().qualified_name());
+            walk_children_mut(self, node);
+        }
+
+        fn visit_attribute_mut(&mut self, node: &Rc<RefCell<Node>>, item: &mut DslAttributeImpl) {
+            let node = node.borrow();
+            let owner = node.ancestors().next().unwrap().0;
+            assert!(Rc::ptr_eq(&node.resolve("..").unwrap(), &owner));
+            item.desc(&format!("{} of {}", item.name_get(), owner.borrow().item().name_get()));
+        }
+    }
+
+    let root = create_model();
+    Describe.visit_node_mut(&root);
+    let order = root.borrow().resolve("shop::Order").unwrap();
+    assert_eq!(order.borrow().item().desc_get(), "shop::Order");
+    let id = root.borrow().resolve("shop::Order::id").unwrap();
+    assert_eq!(id.borrow().item().desc_get(), "id of Order");
+}
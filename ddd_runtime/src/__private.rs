@@ -0,0 +1,32 @@
+//! Paths the code emitted by the derives refers to, not public API.
+
+pub use dyn_clone::{self, DynClone};
+pub use serde;
+pub use typetag;
+
+pub use std::any::Any;
+pub use std::borrow::Cow;
+pub use std::boxed::Box;
+pub use std::cell::RefCell;
+pub use std::clone::Clone;
+pub use std::cmp::PartialEq;
+pub use std::default::Default;
+pub use std::fmt;
+pub use std::marker::{PhantomData, Send, Sync};
+pub use std::option::Option;
+pub use std::path::{Path, PathBuf};
+pub use std::rc::Rc;
+pub use std::result::Result;
+pub use std::str::FromStr;
+pub use std::string::String;
+pub use std::vec::Vec;
+pub use std::{format, panic};
+
+pub use crate::reflect::{debug as reflect_debug, eq as reflect_eq, get as reflect_get, set as reflect_set};
+pub use crate::source::item_source;
+pub use crate::validate::is_match;
+
+/// Glob-imported next to the typetag'd items, typetag's own expansion refers to `typetag::...`.
+pub mod typetag_scope {
+    pub use typetag;
+}
@@ -0,0 +1,12 @@
+//! Everything the DSL derives need at runtime: depend on this crate alone, it
+//! re-exports the derives and pins the versions of the crates their code uses.
+
+pub use ddd_derives::{dsl_visitors, AsDslEnum, AsDslItem};
+
+pub mod reference;
+pub mod reflect;
+pub mod source;
+pub mod validate;
+
+#[doc(hidden)]
+pub mod __private;
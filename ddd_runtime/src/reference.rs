@@ -0,0 +1,104 @@
+use std::any::{type_name, Any};
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A reference to another item by its qualified name, the names of the items
+/// from the root of the model down to it joined with `::`: `shop::order::Money`.
+/// Dots are part of the names, `shop::v1.2::Money` goes through an item named `v1.2`.
+///
+/// `T` is the `Dsl{Ident}Impl` the reference points to. The reference is stored
+/// and serialized as the name alone, `ddd_model::node::Node::resolve_ref` looks
+/// the item up in a model.
+pub struct Ref<T> {
+    name: String,
+    target: PhantomData<fn() -> T>,
+}
+
+impl<T> Ref<T> {
+    pub fn new(name: impl Into<String>) -> Self {
+        Ref {
+            name: name.into(),
+            target: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The names along the path to the item, `["shop", "order", "Money"]`.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.name.split("::")
+    }
+}
+
+impl<T> Clone for Ref<T> {
+    fn clone(&self) -> Self {
+        Ref::new(self.name.clone())
+    }
+}
+
+impl<T> Default for Ref<T> {
+    fn default() -> Self {
+        Ref::new(String::new())
+    }
+}
+
+impl<T> PartialEq for Ref<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl<T> Eq for Ref<T> {}
+
+impl<T> fmt::Debug for Ref<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ref").field(&self.name).finish()
+    }
+}
+
+impl<T> fmt::Display for Ref<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl<T> Serialize for Ref<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Ref<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Ref::new)
+    }
+}
+
+/// A `Ref` with its target type erased, see `Reflect::references`.
+pub trait AnyRef {
+    fn name(&self) -> &str;
+
+    /// Whether `item` has the type the reference points to.
+    fn accepts(&self, item: &dyn Any) -> bool;
+
+    /// Name of the `Dsl{Ident}Impl` the reference points to, for messages.
+    fn target_type_name(&self) -> &'static str;
+}
+
+impl<T: 'static> AnyRef for Ref<T> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn accepts(&self, item: &dyn Any) -> bool {
+        item.is::<T>()
+    }
+
+    fn target_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+}
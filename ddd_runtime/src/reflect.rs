@@ -0,0 +1,256 @@
+use std::any::Any;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::reference::AnyRef;
+
+/// Runtime access to the fields of a derived `Dsl{Ident}Impl`, for tools that
+/// don't know the item type at compile time.
+pub trait Reflect {
+    fn descriptor(&self) -> &'static ItemDescriptor;
+
+    /// The item as `Any`, to downcast it to its `Dsl{Ident}Impl`.
+    fn as_any(&self) -> &dyn Any
+    where
+        Self: 'static;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any
+    where
+        Self: 'static;
+
+    /// The value of the field, the empty value for an unset scalar field.
+    fn get_field(&self, name: &str) -> Result<DynValue, ReflectError>;
+
+    /// Sets the field, `DynValue::None` resets it.
+    fn set_field(&mut self, name: &str, value: DynValue) -> Result<(), ReflectError>;
+
+    /// The `Ref` values that have been set, with the name of their field.
+    /// Those of the extended items come first.
+    fn references(&self) -> Vec<(&'static str, &dyn AnyRef)>;
+}
+
+/// How a field is stored, see the field kinds of `derive(AsDslItem)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Scalar,
+    List,
+    Box,
+    Option,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDescriptor {
+    /// Name of the getter and the setters, without `_get`.
+    pub name: &'static str,
+    /// The Rust type as written in the item.
+    pub ty: &'static str,
+    pub kind: FieldKind,
+    /// The `#[dsl(default)]` literal, `None` if the field starts from the empty value of its type.
+    pub default: Option<&'static str>,
+    pub doc: Option<&'static str>,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemDescriptor {
+    pub name: &'static str,
+    /// The item named by `#[dsl(extends)]`.
+    pub extends: Option<&'static ItemDescriptor>,
+    /// The fields declared by the item itself.
+    pub fields: &'static [FieldDescriptor],
+}
+
+impl ItemDescriptor {
+    /// All fields of the item, those of the extended items first.
+    pub fn all_fields(&self) -> Vec<&'static FieldDescriptor> {
+        let mut fields = self.extends.map(ItemDescriptor::all_fields).unwrap_or_default();
+        fields.extend(self.fields.iter());
+        fields
+    }
+
+    pub fn field(&self, name: &str) -> Option<&'static FieldDescriptor> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .or_else(|| self.extends.and_then(|extends| extends.field(name)))
+    }
+}
+
+/// A field value detached from its Rust type. Child items are carried as `Map`,
+/// with the fields that have been set.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynValue {
+    None,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    List(Vec<DynValue>),
+    Map(Vec<(String, DynValue)>),
+}
+
+impl DynValue {
+    fn from_yaml(value: serde_yaml::Value) -> Self {
+        match value {
+            serde_yaml::Value::Null => DynValue::None,
+            serde_yaml::Value::Bool(value) => DynValue::Bool(value),
+            serde_yaml::Value::Number(number) => {
+                if let Some(value) = number.as_i64() {
+                    DynValue::Int(value)
+                } else if let Some(value) = number.as_u64() {
+                    DynValue::UInt(value)
+                } else {
+                    DynValue::Float(number.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            serde_yaml::Value::String(value) => DynValue::String(value),
+            serde_yaml::Value::Sequence(values) => {
+                DynValue::List(values.into_iter().map(DynValue::from_yaml).collect())
+            }
+            serde_yaml::Value::Mapping(entries) => DynValue::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let key = match key {
+                            serde_yaml::Value::String(key) => key,
+                            key => serde_yaml::to_string(&key).unwrap_or_default().trim().to_owned(),
+                        };
+                        (key, DynValue::from_yaml(value))
+                    })
+                    .collect(),
+            ),
+            serde_yaml::Value::Tagged(tagged) => DynValue::from_yaml(tagged.value),
+        }
+    }
+
+    fn into_yaml(self) -> serde_yaml::Value {
+        match self {
+            DynValue::None => serde_yaml::Value::Null,
+            DynValue::Bool(value) => value.into(),
+            DynValue::Int(value) => value.into(),
+            DynValue::UInt(value) => value.into(),
+            DynValue::Float(value) => value.into(),
+            DynValue::String(value) => value.into(),
+            DynValue::List(values) => {
+                serde_yaml::Value::Sequence(values.into_iter().map(DynValue::into_yaml).collect())
+            }
+            DynValue::Map(entries) => serde_yaml::Value::Mapping(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into_yaml()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<bool> for DynValue {
+    fn from(value: bool) -> Self {
+        DynValue::Bool(value)
+    }
+}
+
+impl From<i64> for DynValue {
+    fn from(value: i64) -> Self {
+        DynValue::Int(value)
+    }
+}
+
+impl From<u64> for DynValue {
+    fn from(value: u64) -> Self {
+        DynValue::UInt(value)
+    }
+}
+
+impl From<f64> for DynValue {
+    fn from(value: f64) -> Self {
+        DynValue::Float(value)
+    }
+}
+
+impl From<&str> for DynValue {
+    fn from(value: &str) -> Self {
+        DynValue::String(value.to_owned())
+    }
+}
+
+impl From<String> for DynValue {
+    fn from(value: String) -> Self {
+        DynValue::String(value)
+    }
+}
+
+impl<T: Into<DynValue>> From<Vec<T>> for DynValue {
+    fn from(values: Vec<T>) -> Self {
+        DynValue::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReflectError {
+    UnknownField(String),
+    /// The value doesn't fit the type of the field.
+    InvalidValue { field: String, message: String },
+}
+
+impl fmt::Display for ReflectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReflectError::UnknownField(field) => write!(f, "unknown field `{field}`"),
+            ReflectError::InvalidValue { field, message } => {
+                write!(f, "invalid value for field `{field}`: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReflectError {}
+
+#[doc(hidden)]
+pub fn get<T: Serialize + ?Sized>(field: &str, value: &T) -> Result<DynValue, ReflectError> {
+    serde_yaml::to_value(value)
+        .map(DynValue::from_yaml)
+        .map_err(|err| invalid_value(field, err))
+}
+
+#[doc(hidden)]
+pub fn set<T: DeserializeOwned>(field: &str, target: &mut T, value: DynValue) -> Result<(), ReflectError> {
+    *target = serde_yaml::from_value(value.into_yaml()).map_err(|err| invalid_value(field, err))?;
+    Ok(())
+}
+
+fn invalid_value(field: &str, err: serde_yaml::Error) -> ReflectError {
+    ReflectError::InvalidValue {
+        field: field.to_owned(),
+        message: err.to_string(),
+    }
+}
+
+/// `Debug` for item trait objects: the item name and the value of every field.
+#[doc(hidden)]
+pub fn debug<T: Reflect + ?Sized>(item: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let descriptor = item.descriptor();
+    let mut debug = f.debug_struct(descriptor.name);
+    for field in descriptor.all_fields() {
+        match item.get_field(field.name) {
+            Ok(value) => debug.field(field.name, &value),
+            Err(err) => debug.field(field.name, &err),
+        };
+    }
+    debug.finish()
+}
+
+/// `PartialEq` for item trait objects: items of the same type whose fields
+/// have equal values.
+#[doc(hidden)]
+pub fn eq<T: Reflect + ?Sized + 'static>(item: &T, other: &T) -> bool {
+    item.as_any().type_id() == other.as_any().type_id()
+        && item
+            .descriptor()
+            .all_fields()
+            .iter()
+            .all(|field| item.get_field(field.name) == other.get_field(field.name))
+}
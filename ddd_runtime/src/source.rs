@@ -0,0 +1,138 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use crate::reference::Ref;
+
+/// Rust source of a value, as written in the builder code printed by the
+/// `to_dsl_source` of a derived `Dsl{Ident}Impl`.
+///
+/// Items are printed as calls of their builders, `dslAttribute(|o| { .. })`,
+/// which have to be in scope where the source is pasted.
+pub trait ToDslSource {
+    /// An expression of the type itself, `String::from("total")`.
+    fn to_dsl_source(&self) -> String;
+
+    /// An expression of the argument taken by the setters of the type,
+    /// `"total"` for a `String` field.
+    fn to_dsl_arg(&self) -> String {
+        self.to_dsl_source()
+    }
+}
+
+impl ToDslSource for bool {
+    fn to_dsl_source(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ToDslSource for char {
+    fn to_dsl_source(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+macro_rules! int_source {
+    ($($ty:ident)*) => {
+        $(
+            impl ToDslSource for $ty {
+                fn to_dsl_source(&self) -> String {
+                    format!("{self}{}", stringify!($ty))
+                }
+            }
+        )*
+    };
+}
+
+int_source!(i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize);
+
+macro_rules! float_source {
+    ($($ty:ident)*) => {
+        $(
+            impl ToDslSource for $ty {
+                fn to_dsl_source(&self) -> String {
+                    if self.is_nan() {
+                        format!("{}::NAN", stringify!($ty))
+                    } else if self.is_infinite() {
+                        let name = if self.is_sign_positive() { "INFINITY" } else { "NEG_INFINITY" };
+                        format!("{}::{name}", stringify!($ty))
+                    } else {
+                        // `Debug` keeps the fraction of whole numbers, `1.0` rather than `1`.
+                        format!("{self:?}{}", stringify!($ty))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+float_source!(f32 f64);
+
+impl ToDslSource for String {
+    fn to_dsl_source(&self) -> String {
+        format!("String::from({self:?})")
+    }
+
+    fn to_dsl_arg(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl ToDslSource for Cow<'static, str> {
+    fn to_dsl_source(&self) -> String {
+        format!("::std::borrow::Cow::Borrowed({:?})", &**self)
+    }
+
+    fn to_dsl_arg(&self) -> String {
+        format!("{:?}", &**self)
+    }
+}
+
+impl ToDslSource for PathBuf {
+    fn to_dsl_source(&self) -> String {
+        format!("::std::path::PathBuf::from({:?})", self.to_string_lossy())
+    }
+
+    fn to_dsl_arg(&self) -> String {
+        format!("::std::path::Path::new({:?})", self.to_string_lossy())
+    }
+}
+
+impl<T> ToDslSource for Ref<T> {
+    fn to_dsl_source(&self) -> String {
+        format!("::ddd_runtime::reference::Ref::new({:?})", self.name())
+    }
+
+    fn to_dsl_arg(&self) -> String {
+        format!("{:?}", self.name())
+    }
+}
+
+impl<T: ToDslSource + ?Sized> ToDslSource for Box<T> {
+    fn to_dsl_source(&self) -> String {
+        format!("Box::new({})", (**self).to_dsl_source())
+    }
+}
+
+/// `builder(|o| { statements })`, or `default_builder()` without statements.
+#[doc(hidden)]
+pub fn item_source(builder: &str, default_builder: &str, statements: &[String]) -> String {
+    if statements.is_empty() {
+        return format!("{default_builder}()");
+    }
+    let mut source = format!("{builder}(|o| {{\n");
+    for statement in statements {
+        source.push_str(&indent(statement));
+        source.push('\n');
+    }
+    source.push_str("})");
+    source
+}
+
+/// Indents every line of `source` by four spaces.
+pub fn indent(source: &str) -> String {
+    source
+        .lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("    {line}") })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
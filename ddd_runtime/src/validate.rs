@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+
+/// A field that failed one of its `#[dsl(validate)]` checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub item: &'static str,
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(item: &'static str, field: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            item,
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}: {}", self.item, self.field, self.message)
+    }
+}
+
+thread_local! {
+    static REGEXES: RefCell<HashMap<&'static str, Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Whether `value` matches `pattern`, the derive has checked that the pattern compiles.
+#[doc(hidden)]
+pub fn is_match(pattern: &'static str, value: &str) -> bool {
+    REGEXES.with(|regexes| {
+        regexes
+            .borrow_mut()
+            .entry(pattern)
+            .or_insert_with(|| Regex::new(pattern).unwrap())
+            .is_match(value)
+    })
+}